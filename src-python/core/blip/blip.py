@@ -4,15 +4,17 @@ from PIL import Image
 from pathlib import Path
 import openvino as ov
 
+from core.config import CAPTION_MODEL_DIR, CAPTION_DEVICE
+
 # 1. Processor와 모델을 로드합니다.
 processor = BlipProcessor.from_pretrained("Salesforce/blip-image-captioning-base")
 model = BlipForConditionalGeneration.from_pretrained("Salesforce/blip-image-captioning-base")
 
-VISION_MODEL_OV = Path("./models/blip_vision_model.xml")
+VISION_MODEL_OV = Path(CAPTION_MODEL_DIR) / "blip_vision_model.xml"
 vision_model = model.vision_model
 vision_model.eval()
 
-TEXT_DECODER_OV = Path("./models/blip_text_decoder_with_past.xml")
+TEXT_DECODER_OV = Path(CAPTION_MODEL_DIR) / "blip_text_decoder_with_past.xml"
 text_decoder = model.text_decoder
 text_decoder.eval()
 
@@ -24,8 +26,8 @@ read_vision_model.reshape({ vision_input_layer.any_name: ov.PartialShape([1, 3,
 read_text_decoder = core.read_model(TEXT_DECODER_OV)
 
 # load models on device
-ov_vision_model = core.compile_model(read_vision_model, "CPU")
-ov_text_decoder_with_past = core.compile_model(read_text_decoder, "CPU")
+ov_vision_model = core.compile_model(read_vision_model, CAPTION_DEVICE)
+ov_text_decoder_with_past = core.compile_model(read_text_decoder, CAPTION_DEVICE)
 
 from functools import partial
 from blip_model import text_decoder_forward, OVBlipModel
@@ -1,7 +1,7 @@
 
-def initialize_vision_model(path):
+def initialize_vision_model(path, model_dir):
     from pathlib import Path
-    VISION_MODEL_OV = Path("./models/blip_vision_model.xml")
+    VISION_MODEL_OV = Path(model_dir) / "blip_vision_model.xml"
 
     if VISION_MODEL_OV.exists():
         return
@@ -28,10 +28,10 @@ def initialize_vision_model(path):
         ov_vision_model = ov.convert_model(vision_model, example_input=inputs["pixel_values"])
     ov.save_model(ov_vision_model, VISION_MODEL_OV)
 
-def initialize_text_decoder():
+def initialize_text_decoder(model_dir):
     from pathlib import Path
 
-    TEXT_DECODER_OV = Path("./models/blip_text_decoder_with_past.xml")
+    TEXT_DECODER_OV = Path(model_dir) / "blip_text_decoder_with_past.xml"
 
     if TEXT_DECODER_OV.exists():
         return
@@ -0,0 +1,14 @@
+# 모델과 저장소 설정
+# Rust에서 실행할 때는 `Config`(src/config.rs)의 값으로 덮어쓰고,
+# `python query.py`처럼 직접 실행할 때는 아래 기본값을 사용합니다.
+
+EMBEDDING_MODEL = "D:\\Intel\\ov_bge-m3"
+EMBEDDING_DEVICE = "NPU"
+
+LLM_MODEL = "D:\\Intel\\ov_EXAONE-3.0-7.8B-Instruct"
+LLM_DEVICE = "GPU"
+
+CAPTION_MODEL_DIR = "./models"
+CAPTION_DEVICE = "CPU"
+
+FAISS_PATH = "db/faiss"
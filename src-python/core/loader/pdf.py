@@ -3,6 +3,21 @@ from langchain_community.document_loaders import PDFPlumberLoader
 from langchain_core.documents import Document
 
 def get_document_from_pdf(file_path: str) -> List[Document]:
+    # 페이지마다 문서 하나로 읽어, 문서 조각의 `start_index`가 페이지에서의 위치가 되게 함
     loader = PDFPlumberLoader(file_path)
-    document_list = loader.load_and_split()
+    document_list = loader.load()
     return document_list
+
+def get_document_from_pages(file_path: str, pages) -> List[Document]:
+    # PDFPlumberLoader와 같이 `page`는 0부터 시작하며, OCR한 글자와 표는 `type`을 "ocr", "table"로 표시
+    # `start_index`는 페이지에서 부분이 시작하는 글자 위치
+    doc = []
+    for number, spans in pages:
+        for text, kind, start in spans:
+            if not text.strip():
+                continue
+            metadata = {"source": file_path, "page": number - 1, "start_index": start}
+            if kind != "text":
+                metadata["type"] = kind
+            doc.append(Document(page_content=text, metadata=metadata))
+    return doc
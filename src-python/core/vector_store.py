@@ -1,4 +1,6 @@
 import os
+import shutil
+import time
 from typing import List
 from faiss import IndexFlatL2
 from langchain_community.vectorstores import FAISS
@@ -7,46 +9,80 @@ from langchain_community.vectorstores.utils import DistanceStrategy
 from langchain_huggingface import HuggingFaceEmbeddings
 
 class VectorStore:
-    DB_FOLDER_PATH_NAME = "db"
-    FAISS_FOLDER_PATH_NAME = "faiss"
+    DEFAULT_DB_FOLDER_PATH = os.path.join("db", "faiss")
     INDEX_FOLDER_PATH_NAME = "faiss_file_index"
 
     INDEX_FILE_NAME = "index.json"
     DB_FILE_NAME = "index.faiss"
+    # 저장할 때마다 새 버전 디렉토리에 쓰고, 이 파일이 가리키는 버전을 바꿈
+    CURRENT_FILE_NAME = "CURRENT"
+    VERSION_FOLDER_PREFIX = "v"
 
-    INDEX_FOLDER_PATH = os.path.join(
-        DB_FOLDER_PATH_NAME, FAISS_FOLDER_PATH_NAME, INDEX_FOLDER_PATH_NAME
-    )
-    INDEX_FILE_PATH = os.path.join(INDEX_FOLDER_PATH, INDEX_FILE_NAME)
-    DB_FOLDER_PATH = os.path.join(DB_FOLDER_PATH_NAME, FAISS_FOLDER_PATH_NAME)
-    DB_FILE_PATH = os.path.join(DB_FOLDER_PATH, DB_FILE_NAME)
-
-    def __init__(self, embeddings_model: HuggingFaceEmbeddings, dimensions: int):
+    def __init__(
+        self,
+        embeddings_model: HuggingFaceEmbeddings,
+        dimensions: int,
+        db_folder_path: str = DEFAULT_DB_FOLDER_PATH,
+    ):
         self.embeddings_model = embeddings_model
         self.dimensions = dimensions
         self.faiss_instance: FAISS | None = None
+        # 파일별 문서 인덱스 (`save`를 호출할 때 FAISS 인덱스와 함께 저장)
+        self.index_objs: List[dict] = []
+        self.dirty = False
+
+        # FAISS 인덱스와 파일별 문서 인덱스(index.json)를 저장하는 경로
+        # 이전 버전은 `db_folder_path`에 직접 저장했으므로, `CURRENT`가 없으면 이 경로에서 읽음
+        self.DB_FOLDER_PATH = db_folder_path
+        self.DB_FILE_PATH = os.path.join(db_folder_path, self.DB_FILE_NAME)
+        self.INDEX_FOLDER_PATH = os.path.join(db_folder_path, self.INDEX_FOLDER_PATH_NAME)
+        self.INDEX_FILE_PATH = os.path.join(self.INDEX_FOLDER_PATH, self.INDEX_FILE_NAME)
+        self.CURRENT_FILE_PATH = os.path.join(db_folder_path, self.CURRENT_FILE_NAME)
 
     def save_index_file(self, file_path: str, index: List[str]):
-        try:
-            import json
-        except ImportError:
-            raise ImportError("json module not found")
         INDEX_OBJ = {
             "file_path": file_path,
             "index": index,
         }
 
-        if not os.path.exists(self.INDEX_FOLDER_PATH):
-            os.makedirs(self.INDEX_FOLDER_PATH)
+        # 같은 파일의 이전 기록은 교체
+        self.index_objs = [
+            index_obj
+            for index_obj in self.index_objs
+            if index_obj["file_path"] != file_path
+        ]
+        self.index_objs.append(INDEX_OBJ)
+        self.dirty = True
 
-        with open(self.INDEX_FILE_PATH, mode="a+") as f:
-            json.dump(INDEX_OBJ, f)
+    def write_index_file(self, folder_path: str, index_objs: List[dict]):
+        try:
+            import json
+        except ImportError:
+            raise ImportError("json module not found")
+
+        with open(os.path.join(folder_path, self.INDEX_FILE_NAME), mode="w") as f:
+            json.dump(index_objs, f)
+
+    def current_folder_path(self):
+        # `CURRENT`가 가리키는 버전 디렉토리 (없으면 이전 버전의 경로)
+        if not os.path.exists(self.CURRENT_FILE_PATH):
+            return None
+        with open(self.CURRENT_FILE_PATH, mode="r") as f:
+            return os.path.join(self.DB_FOLDER_PATH, f.read().strip())
 
     def load_or_create_faiss(self):
         try:
-            if os.path.exists(self.DB_FILE_PATH):
+            current_folder_path = self.current_folder_path()
+            if current_folder_path is not None:
+                db_folder_path = current_folder_path
+                index_file_path = os.path.join(current_folder_path, self.INDEX_FILE_NAME)
+            else:
+                db_folder_path = self.DB_FOLDER_PATH
+                index_file_path = self.INDEX_FILE_PATH
+
+            if os.path.exists(os.path.join(db_folder_path, self.DB_FILE_NAME)):
                 self.faiss_instance = FAISS.load_local(
-                    folder_path=self.DB_FOLDER_PATH,
+                    folder_path=db_folder_path,
                     embeddings=self.embeddings_model,
                     allow_dangerous_deserialization=True,
                 )
@@ -59,6 +95,7 @@ class VectorStore:
                     distance_strategy=DistanceStrategy.COSINE,
                     index_to_docstore_id={},
                 )
+            self.index_objs = self.load_index_file(index_file_path)
         except Exception as e:
             raise Exception(f"Error loading or creating FAISS instance: {e}")
 
@@ -75,15 +112,48 @@ class VectorStore:
                 "FAISS instance is not loaded or created yet. Call `load_or_create_faiss()` first."
             )
 
-        if not os.path.exists(self.DB_FOLDER_PATH):
-            os.makedirs(self.DB_FOLDER_PATH)
+        # 새 버전 디렉토리에 FAISS 인덱스와 파일별 문서 인덱스를 모두 쓴 후 `CURRENT`만 교체하므로,
+        # 저장 중에 종료되더라도 이전 버전이 그대로 남음
+        version = f"{self.VERSION_FOLDER_PREFIX}{time.time_ns()}"
+        folder_path = os.path.join(self.DB_FOLDER_PATH, version)
+        os.makedirs(folder_path)
+        self.faiss_instance.save_local(folder_path)
+        self.write_index_file(folder_path, self.index_objs)
+        # `CURRENT`를 바꾸기 전에 새 버전의 파일이 디스크에 기록되도록 함
+        for file_name in os.listdir(folder_path):
+            with open(os.path.join(folder_path, file_name), mode="rb") as f:
+                os.fsync(f.fileno())
+
+        temp_file_path = self.CURRENT_FILE_PATH + ".tmp"
+        with open(temp_file_path, mode="w") as f:
+            f.write(version)
+            f.flush()
+            os.fsync(f.fileno())
+        os.replace(temp_file_path, self.CURRENT_FILE_PATH)
+        self.remove_old_versions(version)
+
+    def remove_old_versions(self, current_version: str):
+        # 교체된 이전 버전과 저장 중에 종료되어 남은 버전 디렉토리 삭제
+        for name in os.listdir(self.DB_FOLDER_PATH):
+            path = os.path.join(self.DB_FOLDER_PATH, name)
+            if (
+                name != current_version
+                and name.startswith(self.VERSION_FOLDER_PREFIX)
+                and os.path.isdir(path)
+            ):
+                shutil.rmtree(path, ignore_errors=True)
 
-        self.faiss_instance.save_local(self.DB_FOLDER_PATH)
+    def save(self):
+        # 바뀐 내용이 있으면 파일별 문서 인덱스와 FAISS 인덱스를 저장
+        if not self.dirty:
+            return
+        self.save_faiss_instance()
+        self.dirty = False
 
-    def add_documents(
+    def add_embeddings(
         self,
-        text_splitter,
         documents,
+        embeddings,
         document_file_path,
     ):
         if self.faiss_instance is None:
@@ -91,7 +161,85 @@ class VectorStore:
                 "FAISS instance is not loaded or created yet. Call `load_or_create_faiss()` first."
             )
 
-        documents = text_splitter.split_documents(documents)
-        added_documents_index = self.faiss_instance.add_documents(documents)
+        added_documents_index = []
+        if documents:
+            added_documents_index = self.faiss_instance.add_embeddings(
+                text_embeddings=zip(
+                    [document.page_content for document in documents], embeddings
+                ),
+                metadatas=[document.metadata for document in documents],
+            )
 
         self.save_index_file(document_file_path, added_documents_index)
+        return added_documents_index
+
+    def load_index_file(self, index_file_path: str) -> List[dict]:
+        try:
+            import json
+        except ImportError:
+            raise ImportError("json module not found")
+
+        if not os.path.exists(index_file_path):
+            return []
+
+        with open(index_file_path, mode="r") as f:
+            content = f.read()
+
+        # 이전 버전은 객체를 구분자 없이 이어서 저장했으므로 하나씩 읽음
+        decoder = json.JSONDecoder()
+        index_objs = []
+        position = 0
+        while position < len(content):
+            if content[position].isspace():
+                position += 1
+                continue
+            index_obj, position = decoder.raw_decode(content, position)
+            if isinstance(index_obj, list):
+                index_objs.extend(index_obj)
+            else:
+                index_objs.append(index_obj)
+        return index_objs
+
+    def remove_documents(self, document_file_path):
+        if self.faiss_instance is None:
+            raise ValueError(
+                "FAISS instance is not loaded or created yet. Call `load_or_create_faiss()` first."
+            )
+
+        # 디렉토리가 삭제된 경우 하위 파일의 문서도 함께 삭제
+        directory_prefix = os.path.join(document_file_path, "")
+        removed_index = []
+        remaining_index_objs = []
+        for index_obj in self.index_objs:
+            file_path = index_obj["file_path"]
+            if file_path == document_file_path or file_path.startswith(directory_prefix):
+                removed_index.extend(index_obj["index"])
+            else:
+                remaining_index_objs.append(index_obj)
+
+        if len(remaining_index_objs) == len(self.index_objs):
+            return 0
+
+        stored_index = set(self.faiss_instance.index_to_docstore_id.values())
+        stored_removed_index = [index for index in removed_index if index in stored_index]
+        if stored_removed_index:
+            self.faiss_instance.delete(stored_removed_index)
+        self.index_objs = remaining_index_objs
+        self.dirty = True
+
+        return len(removed_index)
+
+    def search(self, query: str, k: int):
+        if self.faiss_instance is None:
+            raise ValueError(
+                "FAISS instance is not loaded or created yet. Call `load_or_create_faiss()` first."
+            )
+
+        return self.faiss_instance.similarity_search_with_relevance_scores(query, k=k)
+
+    def get_sources(self) -> List[tuple]:
+        chunk_counts = {}
+        for index_obj in self.index_objs:
+            file_path = index_obj["file_path"]
+            chunk_counts[file_path] = chunk_counts.get(file_path, 0) + len(index_obj["index"])
+        return sorted(chunk_counts.items())
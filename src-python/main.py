@@ -1,45 +1,48 @@
 from langchain.text_splitter import RecursiveCharacterTextSplitter
-from langchain_community.embeddings import OpenVINOBgeEmbeddings
 
-from core.vector_store import VectorStore
-
-from core.loader.pdf import get_document_from_pdf
+from core.loader.pdf import get_document_from_pdf, get_document_from_pages
 from core.loader.image import get_document_from_image
 
-embeddings_model = OpenVINOBgeEmbeddings(
-    model_name_or_path="D:\\Intel\\ov_bge-m3",
-    model_kwargs={"device": "NPU", "compile": False},
-    encode_kwargs={
-        "mean_pooling": False,
-        "normalize_embeddings": True,
-        "batch_size": 1,
-    },
+text_splitter = RecursiveCharacterTextSplitter(
+    chunk_size=500, chunk_overlap=100, length_function=len, add_start_index=True
 )
-embeddings_model.ov_model.reshape(1, 512)
-embeddings_model.ov_model.compile()
 
-dimensions = len(embeddings_model.embed_query("test"))
-vector_store = VectorStore(embeddings_model, dimensions)
-vector_store.load_or_create_faiss()
+# `src/loader.rs`의 `Loader::name`별 문서 추출 함수
+LOADERS = {
+    "pdf": get_document_from_pdf,
+    "image": get_document_from_image,
+}
 
-text_splitter = RecursiveCharacterTextSplitter(
-    chunk_size=500, chunk_overlap=100, length_function=len
-)
+# 파일 하나를 문서 조각으로 나누는 단계: load_documents → split_documents
+# 이후 단계는 `store.py`의 embed_documents → store_documents
+
+def load_documents(path, loader, metadata):
+    return with_metadata(LOADERS[loader](path), metadata)
+
+def documents_from_pages(path, pages, metadata):
+    # `src/pdf_reader.rs`에서 추출한 [(페이지 번호, [(글자, 종류, 페이지에서의 시작 위치)])]
+    return with_metadata(get_document_from_pages(path, pages), metadata)
+
+def with_metadata(documents, metadata):
+    # OS 인덱스의 문서 속성(제목, 작성자, 종류, 생성 시각)은 로더가 추출한 값을 덮어쓰지 않음
+    for document in documents:
+        for key, value in metadata.items():
+            document.metadata.setdefault(key, value)
+    return documents
+
+def describe_documents(documents):
+    # (페이지 수, OCR 사용 여부, 캡션 사용 여부)
+    pages = {document.metadata.get("page", 0) for document in documents}
+    types = {document.metadata.get("type") for document in documents}
+    return len(pages), "ocr" in types, "caption" in types
 
-def pdf_embed(path):
-    pdf_page_list = get_document_from_pdf(path)
-    vector_store.add_documents(
-        text_splitter,
-        pdf_page_list,
-        path,
-    )
-    vector_store.save_faiss_instance()
-
-def image_embed(path):
-    image_page_list = get_document_from_image(path)
-    vector_store.add_documents(
-        text_splitter,
-        image_page_list,
-        path,
-    )
-    vector_store.save_faiss_instance()
+def split_documents(documents):
+    # 문서 조각의 `start_index`는 문서 안의 위치이므로, 문서가 페이지에서 시작하는 위치를 더해
+    # 페이지에서의 위치로 바꿈 (찾지 못해 -1이면 문서의 시작 위치 사용)
+    chunks = []
+    for document in documents:
+        offset = document.metadata.get("start_index", 0)
+        for chunk in text_splitter.split_documents([document]):
+            chunk.metadata["start_index"] = offset + max(chunk.metadata.get("start_index", 0), 0)
+            chunks.append(chunk)
+    return chunks
@@ -15,12 +15,66 @@ from operator import itemgetter
 
 import os
 
+from core.config import EMBEDDING_MODEL, EMBEDDING_DEVICE, LLM_MODEL, LLM_DEVICE, FAISS_PATH
+
 os.environ["TOKENIZERS_PARALLELISM"] = "false"
 
+SYSTEM_PROMPT = (
+    "You are a helpful file exploration assistant."
+    "Your mission is to check whether the requested information is included in the given document,"
+    "or if the content can be inferred based on the provided document, and to provide an answer to the user’s question."
+    "If the document does not contain relevant information or if the content cannot be inferred, politely state that no matching file exists."
+    "If it does contain an answer, or if it can be inferred by the provided document, provide a brief response to the query along with the file location​"
+    "Do not fabricate or invent any information that is not provided in the document."
+    "Provided source is path of the file."
+    "Please always answer in Korean."
+    "The following is the content of the document searched based on the user's query. : {context}"
+)
+
+answer_llm = None
+
+def create_llm(max_new_tokens: int):
+    llm = HuggingFacePipeline.from_model_id(
+        model_id=LLM_MODEL,
+        task="text-generation",
+        backend="openvino",
+        model_kwargs={
+            "device": LLM_DEVICE,
+            "ov_config": {
+                hints.performance_mode(): hints.PerformanceMode.LATENCY,
+                streams.num(): "1",
+                properties.cache_dir(): ""
+            },
+            "trust_remote_code": True
+        },
+        pipeline_kwargs={"max_new_tokens": max_new_tokens}
+    )
+    if llm.pipeline.tokenizer.eos_token_id:
+        llm.pipeline.tokenizer.pad_token_id = llm.pipeline.tokenizer.eos_token_id
+    return llm
+
+def answer(question: str, documents):
+    global answer_llm
+    if answer_llm is None:
+        answer_llm = create_llm(max_new_tokens=512)
+
+    # documents: 검색된 (내용, 파일 경로) 목록
+    context = "\n\n".join(
+        f"source: {path}\n{content}" for content, path in documents
+    )
+    qa_prompt = ChatPromptTemplate.from_messages(
+        messages=[
+            ("system", SYSTEM_PROMPT),
+            ("human", "{question}"),
+        ]
+    )
+    chain = qa_prompt | answer_llm | StrOutputParser()
+    return chain.invoke({"context": context, "question": question})
+
 def query(file_path: str, query: str):
     embeddings_model = OpenVINOBgeEmbeddings(
-        model_name_or_path="D:\\Intel\\ov_bge-m3",
-        model_kwargs={"device": "NPU", "compile": False},
+        model_name_or_path=EMBEDDING_MODEL,
+        model_kwargs={"device": EMBEDDING_DEVICE, "compile": False},
         encode_kwargs={
             "mean_pooling": False,
             "normalize_embeddings": True,
@@ -34,23 +88,7 @@ def query(file_path: str, query: str):
         file_path, embeddings_model, allow_dangerous_deserialization=True
     )
 
-    llm = HuggingFacePipeline.from_model_id(
-        model_id="D:\\Intel\\ov_EXAONE-3.0-7.8B-Instruct",
-        task="text-generation",
-        backend="openvino",
-        model_kwargs={
-            "device": "GPU",
-            "ov_config": {
-                hints.performance_mode(): hints.PerformanceMode.LATENCY,
-                streams.num(): "1",
-                properties.cache_dir(): ""
-            },
-            "trust_remote_code": True
-        },
-        pipeline_kwargs={"max_new_tokens": 2}
-    )
-    if llm.pipeline.tokenizer.eos_token_id:
-        llm.pipeline.tokenizer.pad_token_id = llm.pipeline.tokenizer.eos_token_id
+    llm = create_llm(max_new_tokens=2)
 
     multi_query_prompt = PromptTemplate.from_template(
         template="""You are an AI language model assistant. Your task is to generate five
@@ -81,21 +119,9 @@ def query(file_path: str, query: str):
         retriever=vector_store.as_retriever(search_kwargs={"k": 2}),
     )
 
-    system_prompt = (
-        "You are a helpful file exploration assistant."
-        "Your mission is to check whether the requested information is included in the given document,"
-        "or if the content can be inferred based on the provided document, and to provide an answer to the user’s question."
-        "If the document does not contain relevant information or if the content cannot be inferred, politely state that no matching file exists."
-        "If it does contain an answer, or if it can be inferred by the provided document, provide a brief response to the query along with the file location​"
-        "Do not fabricate or invent any information that is not provided in the document."
-        "Provided source is path of the file."
-        "Please always answer in Korean."
-        "The following is the content of the document searched based on the user's query. : {context}"
-    )
-
     qa_prompt = ChatPromptTemplate.from_messages(
         messages=[
-            ("system", system_prompt),
+            ("system", SYSTEM_PROMPT),
             ("human", "{question}"),
         ]
     )
@@ -121,6 +147,6 @@ def reorder_documents(docs):
 
 if __name__ == "__main__":
     query(
-        "db/faiss",
+        FAISS_PATH,
         query="AI에서 최근 들어 사용이 더 빈번해진 기술들이 뭐야?",
     )
@@ -0,0 +1,72 @@
+from langchain_community.embeddings import OpenVINOBgeEmbeddings
+
+from core.config import EMBEDDING_MODEL, EMBEDDING_DEVICE, FAISS_PATH
+from core.vector_store import VectorStore
+
+embeddings_model = OpenVINOBgeEmbeddings(
+    model_name_or_path=EMBEDDING_MODEL,
+    model_kwargs={"device": EMBEDDING_DEVICE, "compile": False},
+    encode_kwargs={
+        "mean_pooling": False,
+        "normalize_embeddings": True,
+        "batch_size": 1,
+    },
+)
+embeddings_model.ov_model.reshape(1, 512)
+embeddings_model.ov_model.compile()
+
+dimensions = len(embeddings_model.embed_query("test"))
+vector_store = VectorStore(embeddings_model, dimensions, FAISS_PATH)
+vector_store.load_or_create_faiss()
+
+# 문서 조각을 임베딩하여 저장하는 단계: embed_documents (여러 파일은 embed_batch) → store_documents
+
+def embed_documents(documents):
+    if not documents:
+        return []
+    return embeddings_model.embed_documents([document.page_content for document in documents])
+
+def embed_batch(batches):
+    # 여러 파일의 문서 조각을 한 번에 임베딩한 후 파일별로 나눔
+    embeddings = embed_documents([document for documents in batches for document in documents])
+    result, start = [], 0
+    for documents in batches:
+        result.append(embeddings[start:start + len(documents)])
+        start += len(documents)
+    return result
+
+# 저장과 삭제는 메모리에만 반영되므로 `flush`를 호출해야 파일에 저장됨
+
+def store_documents(path, documents, embeddings):
+    return vector_store.add_embeddings(documents, embeddings, path)
+
+def remove_embed(path):
+    return vector_store.remove_documents(path)
+
+def flush():
+    vector_store.save()
+
+def search(query, k):
+    # (내용, 파일 경로, 관련도, 1부터 시작하는 페이지 번호, 페이지에서의 시작 위치)
+    return [
+        (
+            document.page_content,
+            get_document_path(document),
+            score,
+            get_document_page(document),
+            document.metadata.get("start_index"),
+        )
+        for document, score in vector_store.search(query, k)
+    ]
+
+def sources():
+    return vector_store.get_sources()
+
+def get_document_page(document):
+    # PDF 로더의 `page`는 0부터 시작하며, 이미지에는 없음
+    page = document.metadata.get("page")
+    return None if page is None else page + 1
+
+def get_document_path(document):
+    # PDF는 `source`, 이미지는 `image_path`에 파일 경로가 저장됨
+    return document.metadata.get("source") or document.metadata.get("image_path") or ""
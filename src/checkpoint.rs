@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{cli::IndexArgs, discovery::DiscoverySource};
+
+/// `Vector Store` 디렉토리에 저장하는 기록 파일 이름
+const CHECKPOINT_FILE_NAME: &str = "checkpoint.json";
+
+/// 취소된 `index` 실행을 이어서 하기 위한 기록
+///
+/// 취소될 때까지 저장한 파일은 `Manifest`에 기록되어 있으므로,
+/// 같은 조건으로 다시 검색하면 저장한 파일은 건너뛰고 멈춘 곳부터 이어서 저장한다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub targets: Vec<String>,
+    pub source: DiscoverySource,
+    pub content: bool,
+    pub limit: Option<usize>,
+    /// 취소되기 전까지 처리한 파일 수
+    pub processed: usize,
+}
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    /// (Error, Path)
+    Read(std::io::Error, PathBuf),
+    /// (Error, Path)
+    Parse(serde_json::Error, PathBuf),
+    /// (Error, Path)
+    Write(std::io::Error, PathBuf),
+}
+
+impl std::fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read(_, path) => write!(f, "failed to read checkpoint {}", path.display()),
+            Self::Parse(_, path) => write!(f, "invalid checkpoint {}", path.display()),
+            Self::Write(_, path) => write!(f, "failed to write checkpoint {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read(e, _) | Self::Write(e, _) => Some(e),
+            Self::Parse(e, _) => Some(e),
+        }
+    }
+}
+
+impl Checkpoint {
+    /// `args`로 실행하여 `processed`개의 파일을 처리한 후 취소된 기록을 만드는 함수
+    pub fn new(args: &IndexArgs, processed: usize) -> Self {
+        Self {
+            targets: args.targets.clone(),
+            source: args.source,
+            content: args.content,
+            limit: args.limit,
+            processed,
+        }
+    }
+
+    /// `args`의 검색 조건을 기록된 조건으로 바꾼 인자를 반환하는 함수
+    pub fn resume_args(&self, args: &IndexArgs) -> IndexArgs {
+        IndexArgs {
+            targets: self.targets.clone(),
+            source: self.source,
+            content: self.content,
+            limit: self.limit,
+            ..args.clone()
+        }
+    }
+
+    /// `store_dir`의 기록을 읽는 함수 (취소된 실행이 없으면 `None`)
+    pub fn load(store_dir: &Path) -> Result<Option<Self>, CheckpointError> {
+        let path = store_dir.join(CHECKPOINT_FILE_NAME);
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(CheckpointError::Read(e, path)),
+        };
+
+        serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| CheckpointError::Parse(e, path))
+    }
+
+    /// `store_dir`에 기록을 저장하는 함수
+    ///
+    /// 저장 중에 종료되더라도 기존 파일이 깨지지 않도록 임시 파일에 쓴 후 교체한다.
+    pub fn save(&self, store_dir: &Path) -> Result<(), CheckpointError> {
+        let path = store_dir.join(CHECKPOINT_FILE_NAME);
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|e| CheckpointError::Write(std::io::Error::other(e), path.clone()))?;
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::create_dir_all(store_dir)
+            .and_then(|_| std::fs::write(&temp_path, text))
+            .and_then(|_| std::fs::rename(&temp_path, &path))
+            .map_err(|e| CheckpointError::Write(e, path))
+    }
+
+    /// 모든 파일을 처리한 후 `store_dir`의 기록을 삭제하는 함수
+    pub fn clear(store_dir: &Path) -> Result<(), CheckpointError> {
+        let path = store_dir.join(CHECKPOINT_FILE_NAME);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(CheckpointError::Write(e, path))
+            }
+            _ => Ok(()),
+        }
+    }
+}
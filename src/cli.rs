@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::{
+    config::{Config, ConfigError, StoreBackend},
+    discovery::DiscoverySource,
+};
+
+/// 로컬 파일을 검색하여 `Vector Store`에 저장하고, 저장된 문서로 질문에 답하는 도구
+///
+/// 하위 명령어 없이 실행하면 대화형으로 질문을 검색한다.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// 설정 파일 경로 (기본값: `RAG_CONFIG` 환경 변수 또는 `./rag.toml`)
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// 문서를 저장할 `Vector Store` (설정 파일의 `store.backend`보다 우선)
+    #[arg(long, value_enum, global = true)]
+    pub store: Option<StoreBackend>,
+    /// 임베딩 모델 경로 (설정 파일의 `embedding.model`보다 우선)
+    #[arg(long, global = true)]
+    pub model: Option<PathBuf>,
+    /// 결과 출력 형식
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    pub format: OutputFormat,
+}
+
+impl Cli {
+    /// 설정 파일과 환경 변수를 읽은 후 명령줄 옵션을 적용하여 `Config`를 만드는 함수
+    pub fn load_config(&self) -> Result<Config, ConfigError> {
+        let mut config = Config::load(self.config.as_deref())?;
+        if let Some(store) = self.store {
+            config.store.backend = store;
+        }
+        if let Some(model) = &self.model {
+            config.embedding.model = model.clone();
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 파일을 검색하여 `Vector Store`에 저장
+    Index(IndexArgs),
+    /// 질문과 관련된 문서 조각을 검색
+    Query(QueryArgs),
+    /// 관련된 문서 조각으로 LLM 답변을 생성
+    Ask(QueryArgs),
+    /// `Vector Store`에 저장된 파일 목록을 출력
+    Status,
+    /// 파일 (디렉토리인 경우 하위 파일 전체)의 문서를 `Vector Store`에서 삭제
+    Purge { path: PathBuf },
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct IndexArgs {
+    /// 탐색할 디렉토리 또는 파일 이름 검색어
+    ///
+    /// 존재하는 디렉토리는 검색 범위로, 나머지는 파일 이름 검색어로 사용한다.
+    pub targets: Vec<String>,
+    /// 파일 검색 소스
+    #[arg(long, default_value = "os-index")]
+    pub source: DiscoverySource,
+    /// 검색어를 파일 이름 대신 문서 내용으로 검색 (`--source os-index`에서만 사용 가능)
+    #[arg(long)]
+    pub content: bool,
+    /// 최대 파일 수
+    #[arg(long)]
+    pub limit: Option<usize>,
+    /// 저장 후 `targets`의 디렉토리에서 파일 변경을 감시하여 `Vector Store`에 반영
+    #[arg(long)]
+    pub watch: bool,
+    /// 모델을 불러오지 않고 저장, 갱신, 삭제될 파일과 예상 페이지/문서 조각 수만 출력
+    #[arg(long, conflicts_with = "watch")]
+    pub dry_run: bool,
+    /// 파일별 처리 결과를 JSON 파일로 저장
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+    /// Ctrl-C로 취소된 이전 실행을 같은 검색 조건으로 이어서 실행
+    #[arg(long, conflicts_with_all = ["targets", "source", "content", "limit"])]
+    pub resume: bool,
+}
+
+impl Default for IndexArgs {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            source: DiscoverySource::OsIndex,
+            content: false,
+            limit: None,
+            watch: false,
+            dry_run: false,
+            report: None,
+            resume: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct QueryArgs {
+    pub text: String,
+    /// 가져올 문서 조각 수
+    #[arg(long, default_value_t = 5)]
+    pub top_k: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// 사람이 읽기 위한 형식
+    Text,
+    /// 스크립트에서 사용하기 위한 JSON 형식
+    Json,
+}
@@ -0,0 +1,229 @@
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// 설정 파일을 지정하지 않았을 때 현재 디렉토리에서 찾는 설정 파일
+const DEFAULT_CONFIG_FILE: &str = "rag.toml";
+/// 설정 파일 경로를 지정하는 환경 변수
+const CONFIG_FILE_ENV: &str = "RAG_CONFIG";
+
+/// 모델, 저장소 경로 등의 설정
+///
+/// 기본값 → 설정 파일(TOML) → 환경 변수(`RAG_*`) → 명령줄 옵션 순서로 덮어쓴다.
+///
+/// ```toml
+/// [embedding]
+/// model = "D:\\Intel\\ov_bge-m3"
+/// device = "NPU"
+///
+/// [store]
+/// faiss_path = "db/faiss"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub embedding: EmbeddingConfig,
+    pub llm: LlmConfig,
+    pub caption: CaptionConfig,
+    pub store: StoreConfig,
+    pub pipeline: PipelineConfig,
+}
+
+/// 문서 임베딩 모델 (`src-python/store.py`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmbeddingConfig {
+    /// OpenVINO로 변환된 BGE 모델 디렉토리
+    pub model: PathBuf,
+    /// OpenVINO 장치 (`CPU`, `GPU`, `NPU`)
+    pub device: String,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            model: PathBuf::from("D:\\Intel\\ov_bge-m3"),
+            device: String::from("NPU"),
+        }
+    }
+}
+
+/// 답변 생성 LLM (`src-python/query.py`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LlmConfig {
+    /// OpenVINO로 변환된 LLM 디렉토리
+    pub model: PathBuf,
+    pub device: String,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            model: PathBuf::from("D:\\Intel\\ov_EXAONE-3.0-7.8B-Instruct"),
+            device: String::from("GPU"),
+        }
+    }
+}
+
+/// 이미지 캡션 모델 (`src-python/core/blip`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CaptionConfig {
+    /// OpenVINO IR로 변환된 BLIP 모델을 저장하는 디렉토리
+    pub model_dir: PathBuf,
+    pub device: String,
+}
+
+impl Default for CaptionConfig {
+    fn default() -> Self {
+        Self {
+            model_dir: PathBuf::from("./models"),
+            device: String::from("CPU"),
+        }
+    }
+}
+
+/// 문서를 저장할 `Vector Store`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StoreConfig {
+    pub backend: StoreBackend,
+    /// FAISS 인덱스를 저장하는 디렉토리
+    pub faiss_path: PathBuf,
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        Self {
+            backend: StoreBackend::Faiss,
+            faiss_path: PathBuf::from("db/faiss"),
+        }
+    }
+}
+
+/// `Vector Store` 종류 (`--store`, `RAG_STORE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreBackend {
+    /// `src-python/core/vector_store.py`의 FAISS 저장소
+    Faiss,
+}
+
+/// 파일 저장 파이프라인 (`pipeline::run`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PipelineConfig {
+    /// 단계 사이에 대기할 수 있는 최대 파일 수
+    pub queue_size: usize,
+    /// 파일 해시를 동시에 계산하는 최대 작업 수
+    pub check_workers: usize,
+    /// 바뀐 파일의 문서를 동시에 추출하는 스레드 수
+    pub extract_workers: usize,
+    /// 여러 파일에서 모아 한 번에 임베딩하는 최대 문서 조각 수
+    pub embed_batch_size: usize,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            queue_size: 64,
+            check_workers: std::thread::available_parallelism().map_or(4, |n| n.get()),
+            extract_workers: 2,
+            embed_batch_size: 32,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// (Error, Path)
+    Read(std::io::Error, PathBuf),
+    /// (Error, Path)
+    Parse(toml::de::Error, PathBuf),
+    /// (Variable, Value)
+    InvalidEnv(&'static str, String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read(_, path) => write!(f, "failed to read config {}", path.display()),
+            Self::Parse(_, path) => write!(f, "invalid config {}", path.display()),
+            Self::InvalidEnv(name, value) => write!(f, "invalid value of `{name}`: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read(e, _) => Some(e),
+            Self::Parse(e, _) => Some(e),
+            Self::InvalidEnv(..) => None,
+        }
+    }
+}
+
+impl Config {
+    /// 설정 파일을 읽고 환경 변수를 적용하여 `Config`를 만드는 함수
+    ///
+    /// `path`가 없으면 `RAG_CONFIG` 환경 변수의 경로를 사용하고, 그것도 없으면 현재 디렉토리의
+    /// `rag.toml`을 사용한다. 직접 지정한 설정 파일이 없으면 오류를 반환하고,
+    /// `rag.toml`이 없으면 기본값을 사용한다.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let path = path
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os(CONFIG_FILE_ENV).map(PathBuf::from));
+        let mut config = match path {
+            Some(path) => Self::from_file(&path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            None => Self::default(),
+        };
+        config.apply_env()?;
+
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| ConfigError::Read(e, path.to_path_buf()))?;
+        toml::from_str(&text).map_err(|e| ConfigError::Parse(e, path.to_path_buf()))
+    }
+
+    /// `RAG_*` 환경 변수로 설정 값을 덮어쓰는 함수 (빈 값은 무시)
+    fn apply_env(&mut self) -> Result<(), ConfigError> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        if let Some(value) = var("RAG_EMBEDDING_MODEL") {
+            self.embedding.model = PathBuf::from(value);
+        }
+        if let Some(value) = var("RAG_EMBEDDING_DEVICE") {
+            self.embedding.device = value;
+        }
+        if let Some(value) = var("RAG_LLM_MODEL") {
+            self.llm.model = PathBuf::from(value);
+        }
+        if let Some(value) = var("RAG_LLM_DEVICE") {
+            self.llm.device = value;
+        }
+        if let Some(value) = var("RAG_CAPTION_MODEL_DIR") {
+            self.caption.model_dir = PathBuf::from(value);
+        }
+        if let Some(value) = var("RAG_CAPTION_DEVICE") {
+            self.caption.device = value;
+        }
+        if let Some(value) = var("RAG_STORE") {
+            self.store.backend = StoreBackend::from_str(&value, true)
+                .map_err(|_| ConfigError::InvalidEnv("RAG_STORE", value))?;
+        }
+        if let Some(value) = var("RAG_FAISS_PATH") {
+            self.store.faiss_path = PathBuf::from(value);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,378 @@
+use std::{
+    collections::HashSet,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::native::{FileHit, HitSource};
+
+/// 심볼릭 링크를 만났을 때의 처리 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// 심볼릭 링크는 탐색하지 않음
+    Skip,
+    /// 심볼릭 링크를 따라가되, 이미 방문한 디렉토리는 다시 탐색하지 않음
+    Follow,
+}
+
+/// 파일 시스템 탐색 옵션
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    /// 탐색을 시작할 디렉토리 목록
+    pub roots: Vec<PathBuf>,
+    /// 포함할 파일의 glob 패턴 (비어 있으면 모든 파일)
+    pub include: Vec<String>,
+    /// 제외할 파일 및 디렉토리의 glob 패턴
+    pub exclude: Vec<String>,
+    /// 각 디렉토리에서 읽을 `.gitignore` 형식의 무시 파일 이름
+    pub ignore_file_names: Vec<String>,
+    /// 루트 디렉토리로부터의 최대 탐색 깊이 (`None`이면 제한 없음)
+    pub max_depth: Option<usize>,
+    pub symlink_policy: SymlinkPolicy,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// 최대 결과 수 (`None`이면 제한 없음)
+    pub max_results: Option<usize>,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        Self {
+            roots: home_dir().into_iter().collect(),
+            include: ["*.pdf", "*.png", "*.jpg", "*.jpeg", "*.tif", "*.tiff"]
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+            exclude: ["node_modules", "target"]
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+            ignore_file_names: vec![".gitignore".to_string(), ".ignore".to_string()],
+            max_depth: None,
+            symlink_policy: SymlinkPolicy::Skip,
+            min_size: None,
+            max_size: Some(10_000_000),
+            max_results: None,
+        }
+    }
+}
+
+/// OS 인덱스를 사용하지 않고 `options.roots`를 직접 탐색하여 `query`에 해당하는 파일을 찾는 함수
+///
+/// 반환값은 `native::search_local_files`와 동일하게 `FileHit`을 하나씩 반환하는 반복자이며,
+/// 디렉토리는 결과를 요청할 때 필요한 만큼만 읽는다.
+/// 루트 디렉토리를 읽을 수 없으면 오류와 경로를 반환한 후 다음 루트 디렉토리를 탐색한다.
+pub fn crawl(query: &str, options: &CrawlOptions) -> Crawler {
+    Crawler {
+        query: query.to_lowercase(),
+        options: options.clone(),
+        visited: HashSet::new(),
+        roots: options.roots.iter().rev().cloned().collect(),
+        stack: Vec::new(),
+        found: 0,
+    }
+}
+
+/// 파일 시스템 탐색 결과 반복자
+pub struct Crawler {
+    query: String,
+    options: CrawlOptions,
+    /// `SymlinkPolicy::Follow`일 때 순환 참조를 막기 위해 방문한 디렉토리의 실제 경로
+    visited: HashSet<PathBuf>,
+    /// 아직 탐색하지 않은 루트 디렉토리 (마지막 항목부터 탐색)
+    roots: Vec<PathBuf>,
+    /// 탐색 중인 디렉토리 (마지막 항목이 가장 깊은 디렉토리)
+    stack: Vec<Directory>,
+    found: usize,
+}
+
+/// 탐색 중인 디렉토리
+struct Directory {
+    root: Arc<Path>,
+    depth: usize,
+    rules: Arc<[IgnoreRule]>,
+    entries: std::fs::ReadDir,
+}
+
+impl Iterator for Crawler {
+    type Item = Result<FileHit, (std::io::Error, PathBuf)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.is_full() {
+                return None;
+            }
+            let Some(directory) = self.stack.last_mut() else {
+                let root = self.roots.pop()?;
+                if let Err(e) = self.enter_root(&root) {
+                    return Some(Err(e));
+                }
+                continue;
+            };
+            let Some(entry) = directory.entries.next() else {
+                self.stack.pop();
+                continue;
+            };
+            let (root, depth, rules) = (
+                directory.root.clone(),
+                directory.depth,
+                directory.rules.clone(),
+            );
+            if let Some(hit) = self.visit(entry, &root, depth, &rules) {
+                self.found += 1;
+                return Some(Ok(hit));
+            }
+        }
+    }
+}
+
+impl Crawler {
+    fn is_full(&self) -> bool {
+        self.options
+            .max_results
+            .is_some_and(|max| self.found >= max)
+    }
+
+    fn enter_root(&mut self, root: &Path) -> Result<(), (std::io::Error, PathBuf)> {
+        let metadata = std::fs::metadata(root).map_err(|e| (e, root.to_path_buf()))?;
+        if !metadata.is_dir() {
+            return Err((ErrorKind::NotADirectory.into(), root.to_path_buf()));
+        }
+        self.enter(Arc::from(root), root, 0, &[])
+            .map_err(|e| (e, root.to_path_buf()))
+    }
+
+    /// 디렉토리의 무시 파일을 읽고 탐색할 디렉토리에 추가하는 함수
+    fn enter(
+        &mut self,
+        root: Arc<Path>,
+        directory: &Path,
+        depth: usize,
+        parent_rules: &[IgnoreRule],
+    ) -> std::io::Result<()> {
+        if self.options.symlink_policy == SymlinkPolicy::Follow {
+            let canonical = directory.canonicalize()?;
+            if !self.visited.insert(canonical) {
+                return Ok(());
+            }
+        }
+
+        let mut rules = parent_rules.to_vec();
+        for ignore_file_name in &self.options.ignore_file_names {
+            rules.extend(read_ignore_file(
+                &directory.join(ignore_file_name),
+                directory,
+            ));
+        }
+
+        let entries = std::fs::read_dir(directory)?;
+        self.stack.push(Directory {
+            root,
+            depth,
+            rules: rules.into(),
+            entries,
+        });
+
+        Ok(())
+    }
+
+    /// 디렉토리 항목이 조건에 해당하는 파일이면 반환하고, 디렉토리면 탐색할 디렉토리에 추가하는 함수
+    fn visit(
+        &mut self,
+        entry: std::io::Result<std::fs::DirEntry>,
+        root: &Arc<Path>,
+        depth: usize,
+        rules: &[IgnoreRule],
+    ) -> Option<FileHit> {
+        let entry = entry.ok()?;
+        let path = entry.path();
+        let file_type = entry.file_type().ok()?;
+        let is_symlink = file_type.is_symlink();
+        if is_symlink && self.options.symlink_policy == SymlinkPolicy::Skip {
+            return None;
+        }
+        // 심볼릭 링크인 경우 링크가 가리키는 대상의 메타데이터를 사용
+        let metadata = if is_symlink {
+            std::fs::metadata(&path)
+        } else {
+            entry.metadata()
+        }
+        .ok()?;
+
+        let is_dir = metadata.is_dir();
+        let relative = relative_path(root, &path);
+        if self.is_excluded(&relative) || is_ignored(rules, &path, is_dir) {
+            return None;
+        }
+
+        if is_dir {
+            if self.options.max_depth.is_none_or(|max| depth < max) {
+                // 권한이 없는 하위 디렉토리는 무시
+                let _ = self.enter(root.clone(), &path, depth + 1, rules);
+            }
+            None
+        } else if metadata.is_file()
+            && self.is_included(&relative)
+            && self.matches_query(&path)
+            && self.matches_size(metadata.len())
+        {
+            Some(FileHit::from_metadata(path, &metadata, HitSource::Crawl))
+        } else {
+            None
+        }
+    }
+
+    fn is_included(&self, relative: &str) -> bool {
+        self.options.include.is_empty()
+            || self
+                .options
+                .include
+                .iter()
+                .any(|pattern| matches_path_pattern(pattern, relative))
+    }
+
+    fn is_excluded(&self, relative: &str) -> bool {
+        self.options
+            .exclude
+            .iter()
+            .any(|pattern| matches_path_pattern(pattern, relative))
+    }
+
+    fn matches_query(&self, path: &Path) -> bool {
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_lowercase().contains(&self.query))
+            .unwrap_or(false)
+    }
+
+    fn matches_size(&self, size: u64) -> bool {
+        self.options.min_size.is_none_or(|min| size >= min)
+            && self.options.max_size.is_none_or(|max| size < max)
+    }
+}
+
+/// `.gitignore` 형식 파일의 한 줄에 해당하는 규칙
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    /// 규칙이 정의된 무시 파일이 위치한 디렉토리
+    base: PathBuf,
+    pattern: String,
+    /// `!`로 시작하는 규칙 (무시하지 않음)
+    negated: bool,
+    /// `/`로 끝나는 규칙 (디렉토리에만 적용)
+    directory_only: bool,
+}
+
+/// 무시 파일을 읽어 규칙 목록을 생성하는 함수
+fn read_ignore_file(path: &Path, base: &Path) -> Vec<IgnoreRule> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (negated, line) = match line.strip_prefix('!') {
+                Some(line) => (true, line),
+                None => (false, line),
+            };
+            let (directory_only, line) = match line.strip_suffix('/') {
+                Some(line) => (true, line),
+                None => (false, line),
+            };
+            IgnoreRule {
+                base: base.to_path_buf(),
+                pattern: line.to_string(),
+                negated,
+                directory_only,
+            }
+        })
+        .collect()
+}
+
+/// 무시 규칙에 해당하는 경로인지 확인하는 함수 (마지막으로 일치한 규칙이 우선)
+fn is_ignored(rules: &[IgnoreRule], path: &Path, is_dir: bool) -> bool {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| {
+            (!rule.directory_only || is_dir)
+                && path.starts_with(&rule.base)
+                && matches_path_pattern(&rule.pattern, &relative_path(&rule.base, path))
+        })
+        .is_some_and(|rule| !rule.negated)
+}
+
+/// `/`로 구분된 상대 경로가 glob 패턴에 해당하는지 확인하는 함수
+///
+/// `.gitignore`와 같이 `/`를 포함하지 않는 패턴은 경로의 어느 구성 요소와도 일치할 수 있고,
+/// `/`를 포함하는 패턴은 상대 경로 전체와 비교한다.
+fn matches_path_pattern(pattern: &str, relative: &str) -> bool {
+    let anchored = pattern.trim_start_matches('/');
+    if pattern.contains('/') {
+        glob_match(anchored, relative)
+    } else {
+        relative
+            .split('/')
+            .any(|component| glob_match(pattern, component))
+    }
+}
+
+/// `*`, `?`, `**`를 지원하는 glob 패턴 비교 함수 (대소문자 구분 없음)
+///
+/// `*`와 `?`는 `/`와 일치하지 않고, `**`는 `/`를 포함한 모든 문자열과 일치한다.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    glob_match_chars(&pattern, &text)
+}
+
+fn glob_match_chars(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            // `**/`는 0개 이상의 디렉토리와 일치
+            let rest_without_slash = rest.strip_prefix(&['/']).unwrap_or(rest);
+            (0..=text.len()).any(|i| {
+                glob_match_chars(rest, &text[i..])
+                    || glob_match_chars(rest_without_slash, &text[i..])
+            })
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if glob_match_chars(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => {
+            text.first().is_some_and(|c| *c != '/') && glob_match_chars(&pattern[1..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && glob_match_chars(&pattern[1..], &text[1..]),
+    }
+}
+
+/// `root` 기준의 상대 경로를 `/`로 구분된 문자열로 변환하는 함수
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
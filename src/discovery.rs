@@ -0,0 +1,144 @@
+use std::{collections::HashSet, path::PathBuf, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crawler::{self, CrawlOptions},
+    native::{self, FileHit, IntoPages, QueryMode, SearchFilter, SearchOptions},
+};
+
+/// 파일 검색에 사용할 소스
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiscoverySource {
+    /// OS 검색 인덱스 (Windows Search, Spotlight, locate)
+    OsIndex,
+    /// 파일 시스템 직접 탐색
+    Crawl,
+    /// OS 검색 인덱스와 파일 시스템 탐색 결과를 합침
+    Both,
+}
+
+impl FromStr for DiscoverySource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "os-index" => Ok(Self::OsIndex),
+            "crawl" => Ok(Self::Crawl),
+            "both" => Ok(Self::Both),
+            _ => Err(format!(
+                "Unknown discovery source: {s} (expected: os-index, crawl, both)"
+            )),
+        }
+    }
+}
+
+/// 파일 검색 중 발생한 오류
+#[derive(Debug)]
+pub enum DiscoveryError {
+    /// OS 검색 인덱스 오류
+    OsIndex(native::SearchLocalFileError),
+    /// 파일 시스템 탐색 오류
+    /// (Error, Path)
+    Crawl(std::io::Error, PathBuf),
+    /// 파일 시스템 탐색은 문서 내용을 알 수 없어 `QueryMode::Content`로 검색할 수 없음
+    ContentUnsupported,
+}
+
+impl DiscoveryError {
+    /// 이 오류 대신 사용할 수 있는 검색 소스를 반환하는 함수
+    pub fn fallback_source(&self) -> Option<DiscoverySource> {
+        match self {
+            Self::OsIndex(_) => Some(DiscoverySource::Crawl),
+            Self::Crawl(..) => Some(DiscoverySource::OsIndex),
+            Self::ContentUnsupported => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DiscoveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OsIndex(_) => write!(f, "OS index search failed"),
+            Self::Crawl(_, path) => write!(f, "crawl failed: {}", path.display()),
+            Self::ContentUnsupported => write!(
+                f,
+                "crawl cannot search document contents; use `--source os-index` with `--content`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DiscoveryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::OsIndex(e) => Some(e),
+            Self::Crawl(e, _) => Some(e),
+            Self::ContentUnsupported => None,
+        }
+    }
+}
+
+/// 검색 결과를 페이지 단위로 가져오는 반복자
+pub type DiscoveryPages = Box<dyn Iterator<Item = Result<Vec<FileHit>, DiscoveryError>>>;
+
+/// `source`에 해당하는 방식으로 `query`와 `filter`에 해당하는 파일을 검색하여,
+/// 결과를 `search_options.page_size`개씩 가져오는 함수
+///
+/// OS 인덱스의 결과는 페이지를 요청할 때마다 가져오고, 파일 시스템 탐색은
+/// OS 인덱스의 결과를 모두 가져온 후에 시작한다.
+/// `prefetched`가 있으면 OS 인덱스를 다시 검색하지 않고 미리 가져온 결과(`native::search_local_files_async`)를 사용한다.
+/// 파일 시스템 탐색은 문서 내용을 알 수 없으므로, `QueryMode::Content`에서 파일 시스템 탐색을
+/// 사용하면 모든 파일을 후보로 반환하는 대신 `DiscoveryError::ContentUnsupported`를 반환한다.
+pub fn discover_pages(
+    query: &str,
+    source: DiscoverySource,
+    filter: &SearchFilter,
+    crawl_options: &CrawlOptions,
+    search_options: &SearchOptions,
+    prefetched: Option<&[FileHit]>,
+) -> Result<DiscoveryPages, DiscoveryError> {
+    let crawl = matches!(source, DiscoverySource::Crawl | DiscoverySource::Both);
+    if crawl && search_options.mode == QueryMode::Content {
+        return Err(DiscoveryError::ContentUnsupported);
+    }
+
+    let mut hits: Box<dyn Iterator<Item = Result<FileHit, DiscoveryError>>> =
+        Box::new(std::iter::empty());
+    if matches!(source, DiscoverySource::OsIndex | DiscoverySource::Both) {
+        let os_hits: Box<dyn Iterator<Item = Result<FileHit, DiscoveryError>>> = match prefetched {
+            Some(prefetched) => Box::new(Vec::from(prefetched).into_iter().map(Ok)),
+            None => Box::new(
+                native::search_local_files(query.to_string(), filter, search_options)
+                    .map_err(DiscoveryError::OsIndex)?
+                    .map(|hit| hit.map_err(DiscoveryError::OsIndex)),
+            ),
+        };
+        hits = Box::new(hits.chain(os_hits));
+    }
+    if crawl {
+        let query = query.to_string();
+        let filter = filter.clone();
+        let crawl_options = crawl_options.clone();
+        // OS 인덱스의 결과를 모두 가져온 후에 탐색을 시작하도록 첫 결과를 요청할 때 반복자를 만듦
+        let crawl_hits = std::iter::once(())
+            .flat_map(move |_| crawler::crawl(&query, &crawl_options))
+            .filter(move |hit| hit.as_ref().map_or(true, |hit| filter.matches(hit)))
+            .map(|hit| hit.map_err(|(e, path)| DiscoveryError::Crawl(e, path)));
+        hits = Box::new(hits.chain(crawl_hits));
+    }
+
+    // 두 소스에서 같은 파일이 검색된 경우 먼저 검색된 결과만 남김
+    let mut paths = HashSet::new();
+    let hits = hits.filter(move |hit| {
+        hit.as_ref()
+            .map_or(true, |hit| paths.insert(hit.path.clone()))
+    });
+    let hits = match search_options.limit {
+        Some(limit) => Box::new(hits.take(limit)) as Box<dyn Iterator<Item = _>>,
+        None => Box::new(hits),
+    };
+
+    Ok(Box::new(hits.pages(search_options.page_size)))
+}
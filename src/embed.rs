@@ -0,0 +1,468 @@
+use std::{path::PathBuf, sync::OnceLock};
+
+use pyo3::{
+    types::{PyAnyMethods, PyList, PyModule, PyTracebackMethods, PyTuple},
+    Py, PyAny, PyErr, PyResult, Python,
+};
+
+use crate::{config::Config, loader::Loader, native::FileHit, pdf_reader};
+
+/// 이미지 캡션 모델(BLIP)을 OpenVINO IR로 변환하여 `model_dir`에 저장하는 함수
+///
+/// 변환된 모델이 이미 있으면 아무 작업도 하지 않는다.
+/// `core.blip.blip` 모듈은 가져올 때 변환된 모델을 읽으므로 `Embedder::load`보다 먼저 호출해야 한다.
+pub fn initialize_vision_model(
+    py: Python<'_>,
+    image_path: &std::path::Path,
+    model_dir: &std::path::Path,
+) -> PyResult<()> {
+    let initalize_module = PyModule::from_code_bound(
+        py,
+        include_str!("../src-python/core/blip/blip_model.py"),
+        "blip_model.py",
+        "blip_model",
+    )?;
+    let initialize_vision_model: Py<PyAny> =
+        initalize_module.getattr("initialize_vision_model")?.into();
+    let initialize_text_decoder: Py<PyAny> =
+        initalize_module.getattr("initialize_text_decoder")?.into();
+
+    let model_dir = model_dir.to_string_lossy();
+    initialize_vision_model.call1(py, (image_path.to_string_lossy(), model_dir.as_ref()))?;
+    initialize_text_decoder.call1(py, (model_dir,))?;
+    Ok(())
+}
+
+/// `config`의 값으로 `core.config` 모듈을 만드는 함수
+///
+/// `src-python`의 모듈들은 가져올 때 `core.config`의 값을 읽으므로, 모듈을 가져오기 전에 호출해야 한다.
+fn register_config(py: Python<'_>, config: &Config) -> PyResult<()> {
+    let module = PyModule::from_code_bound(
+        py,
+        include_str!("../src-python/core/config.py"),
+        "config.py",
+        "core.config",
+    )?;
+    module.setattr("EMBEDDING_MODEL", config.embedding.model.to_string_lossy())?;
+    module.setattr("EMBEDDING_DEVICE", &config.embedding.device)?;
+    module.setattr("LLM_MODEL", config.llm.model.to_string_lossy())?;
+    module.setattr("LLM_DEVICE", &config.llm.device)?;
+    module.setattr(
+        "CAPTION_MODEL_DIR",
+        config.caption.model_dir.to_string_lossy(),
+    )?;
+    module.setattr("CAPTION_DEVICE", &config.caption.device)?;
+    module.setattr("FAISS_PATH", config.store.faiss_path.to_string_lossy())?;
+    Ok(())
+}
+
+/// 파일을 문서 조각으로 나누는 로더의 버전
+///
+/// 로더나 문서 분할 방식이 바뀌면 올려서, 이전 버전으로 저장된 파일을 다시 저장하게 한다.
+pub const LOADER_VERSION: u32 = 2;
+
+/// `Vector Store`에서 검색된 문서 조각
+#[derive(Debug, Clone)]
+pub struct RetrievedChunk {
+    pub content: String,
+    /// 문서 조각을 추출한 파일 경로
+    pub path: PathBuf,
+    /// 질문과의 관련도 (0 ~ 1, 높을수록 관련이 높음)
+    pub score: f64,
+    /// 문서 조각이 있는 페이지 번호 (1부터 시작, 이미지는 `None`)
+    pub page: Option<u32>,
+    /// 페이지에서 문서 조각이 시작하는 글자(`char`) 위치
+    pub offset: Option<usize>,
+}
+
+/// `store.py`의 `search`가 반환하는 (내용, 파일 경로, 관련도, 페이지 번호, 페이지에서의 위치)
+type SearchResult = (String, String, f64, Option<u32>, Option<usize>);
+
+/// `Python` 오류를 traceback을 포함한 문자열로 만드는 함수
+pub fn format_py_error(py: Python<'_>, e: &PyErr) -> String {
+    match e.traceback_bound(py).map(|traceback| traceback.format()) {
+        Some(Ok(traceback)) => format!("{traceback}{e}"),
+        _ => e.to_string(),
+    }
+}
+
+/// `embed_file`로 저장한 파일의 정보
+#[derive(Debug, Clone)]
+pub struct EmbeddedFile {
+    /// 저장된 문서 조각의 `Vector Store` ID
+    pub chunk_ids: Vec<String>,
+    pub pages: usize,
+    pub ocr: bool,
+    pub caption: bool,
+}
+
+/// `DocumentLoader::extract`로 파일에서 추출하여 나눈 문서 조각
+pub struct FileChunks {
+    /// 문서 조각 목록 (`Python` 객체)
+    pub chunks: Py<PyAny>,
+    /// 문서 조각 수
+    pub len: usize,
+    pub pages: usize,
+    pub ocr: bool,
+    pub caption: bool,
+}
+
+/// 명령어를 실행하는 동안 필요할 때 한 번만 불러오는 `Python` 모듈들
+///
+/// 검색, 삭제, 상태 확인에는 `Embedder`만 필요하므로, 이미지 캡션 모델과 문서 로더는
+/// 파일을 저장할 때만 불러온다. 불러온 모듈은 여러 스레드에서 함께 사용할 수 있으며,
+/// 여러 스레드에서 동시에 처음 불러오면 먼저 불러온 값을 사용한다.
+#[derive(Default)]
+pub struct Models {
+    embedder: OnceLock<Embedder>,
+    document_loader: OnceLock<DocumentLoader>,
+}
+
+impl Models {
+    /// 처음 호출될 때 임베딩 모델과 `Vector Store`를 불러오고, 이후에는 불러온 값을 반환하는 함수
+    pub fn embedder(&self, py: Python<'_>, config: &Config) -> PyResult<&Embedder> {
+        if let Some(embedder) = self.embedder.get() {
+            return Ok(embedder);
+        }
+        let embedder = Embedder::load(py, config)?;
+
+        Ok(self.embedder.get_or_init(|| embedder))
+    }
+
+    /// 파일 저장에 필요한 `DocumentLoader`와 `Embedder`를 처음 호출될 때 불러오는 함수
+    ///
+    /// `hits`에 이미지가 있으면 모듈을 가져오기 전에 이미지 캡션 모델을 준비한다.
+    pub fn ingest(
+        &self,
+        py: Python<'_>,
+        hits: &[FileHit],
+        config: &Config,
+    ) -> PyResult<(&DocumentLoader, &Embedder)> {
+        let document_loader = match self.document_loader.get() {
+            Some(document_loader) => document_loader,
+            None => {
+                if let Some(hit) = hits
+                    .iter()
+                    .find(|hit| Loader::for_mime(hit.mime) == Some(Loader::Image))
+                {
+                    // 모델 변환에 실패하더라도 PDF 파일은 처리할 수 있으므로 무시
+                    let _ = initialize_vision_model(py, &hit.path, &config.caption.model_dir);
+                }
+                let document_loader = DocumentLoader::load(py, config)?;
+                self.document_loader.get_or_init(|| document_loader)
+            }
+        };
+
+        Ok((document_loader, self.embedder(py, config)?))
+    }
+
+    /// 이미 불러온 `Embedder`를 반환하는 함수 (불러오지 않았으면 `None`)
+    pub fn loaded_embedder(&self) -> Option<&Embedder> {
+        self.embedder.get()
+    }
+}
+
+/// 파일을 `loader`로 추출하여 모든 단계를 거쳐 `Vector Store`에 저장하는 함수
+///
+/// 파일 하나는 `load_documents` → `split_documents` → `embed_documents` → `store_documents`
+/// 단계로 저장한다. 단계 사이의 문서 목록은 `Python` 객체 그대로 전달한다.
+pub fn embed_file(
+    py: Python<'_>,
+    document_loader: &DocumentLoader,
+    embedder: &Embedder,
+    hit: &FileHit,
+    loader: Loader,
+) -> PyResult<EmbeddedFile> {
+    let file = document_loader.extract(py, hit, loader)?;
+    let embeddings = embedder.embed_documents(py, &file.chunks)?;
+    let chunk_ids = embedder.store_documents(py, hit, &file.chunks, &embeddings)?;
+
+    Ok(EmbeddedFile {
+        chunk_ids,
+        pages: file.pages,
+        ocr: file.ocr,
+        caption: file.caption,
+    })
+}
+
+/// 파일에서 문서 목록을 추출하여 문서 조각으로 나누는 `Python` 함수들 (`src-python/main.py`)
+pub struct DocumentLoader {
+    load_documents_func: Py<PyAny>,
+    documents_from_pages_func: Py<PyAny>,
+    describe_documents_func: Py<PyAny>,
+    split_documents_func: Py<PyAny>,
+}
+
+impl DocumentLoader {
+    /// `config`로 문서 로더와 이미지 캡션 모듈을 가져와 `DocumentLoader`를 생성하는 함수
+    pub fn load(py: Python<'_>, config: &Config) -> PyResult<Self> {
+        register_config(py, config)?;
+        PyModule::from_code_bound(
+            py,
+            include_str!("../src-python/core/blip/blip_model.py"),
+            "blip_model.py",
+            "core.blip.blip_model",
+        )?;
+        PyModule::from_code_bound(
+            py,
+            include_str!("../src-python/core/blip/blip.py"),
+            "blip.py",
+            "core.blip.blip",
+        )?;
+        PyModule::from_code_bound(
+            py,
+            include_str!("../src-python/core/loader/pdf.py"),
+            "pdf.py",
+            "core.loader.pdf",
+        )?;
+        PyModule::from_code_bound(
+            py,
+            include_str!("../src-python/core/loader/image.py"),
+            "image.py",
+            "core.loader.image",
+        )?;
+        let module = PyModule::from_code_bound(
+            py,
+            include_str!("../src-python/main.py"),
+            "main.py",
+            "main",
+        )?;
+
+        Ok(Self {
+            load_documents_func: module.getattr("load_documents")?.into(),
+            documents_from_pages_func: module.getattr("documents_from_pages")?.into(),
+            describe_documents_func: module.getattr("describe_documents")?.into(),
+            split_documents_func: module.getattr("split_documents")?.into(),
+        })
+    }
+
+    /// `loader`로 파일에서 문서 목록을 추출하는 함수
+    ///
+    /// PDF는 `pdf_reader`로 페이지별 글자와 표, 이미지를 OCR한 글자를 추출하며, GIL을 놓고 실행하므로
+    /// 다른 스레드의 `Python` 단계와 겹쳐서 실행된다. `pdf_reader`가 PDF를 읽지 못하면 PDFPlumber를 사용한다.
+    pub fn load_documents(
+        &self,
+        py: Python<'_>,
+        hit: &FileHit,
+        loader: Loader,
+    ) -> PyResult<Py<PyAny>> {
+        if loader == Loader::Pdf {
+            match py.allow_threads(|| pdf_reader::read_pdf_file(&hit.path)) {
+                Ok(document) => return self.documents_from_pages(py, hit, &document),
+                Err(e) => eprintln!("Falling back to PDFPlumber for {}: {e}", hit.path.display()),
+            }
+        }
+
+        self.load_documents_func.call1(
+            py,
+            (
+                hit.path.to_string_lossy(),
+                loader.name(),
+                hit.properties.to_metadata(),
+            ),
+        )
+    }
+
+    /// `pdf_reader`로 추출한 페이지들로 문서 목록을 만드는 함수
+    fn documents_from_pages(
+        &self,
+        py: Python<'_>,
+        hit: &FileHit,
+        document: &pdf_reader::ExtractedDocument,
+    ) -> PyResult<Py<PyAny>> {
+        let pages = document
+            .pages
+            .iter()
+            .map(|page| {
+                let spans = page
+                    .span_texts()
+                    .into_iter()
+                    .map(|(text, kind, start)| (text, kind.as_str(), start))
+                    .collect::<Vec<_>>();
+                (page.number, spans)
+            })
+            .collect::<Vec<_>>();
+        self.documents_from_pages_func.call1(
+            py,
+            (
+                hit.path.to_string_lossy(),
+                pages,
+                hit.properties.to_metadata(),
+            ),
+        )
+    }
+
+    /// 추출한 문서 목록의 (페이지 수, OCR 사용 여부, 캡션 사용 여부)를 반환하는 함수
+    pub fn describe_documents(
+        &self,
+        py: Python<'_>,
+        documents: &Py<PyAny>,
+    ) -> PyResult<(usize, bool, bool)> {
+        self.describe_documents_func
+            .call1(py, (documents,))?
+            .extract(py)
+    }
+
+    /// 문서 목록을 문서 조각으로 나누는 함수
+    pub fn split_documents(&self, py: Python<'_>, documents: &Py<PyAny>) -> PyResult<Py<PyAny>> {
+        self.split_documents_func.call1(py, (documents,))
+    }
+
+    /// `loader`로 파일에서 문서 목록을 추출하여 문서 조각으로 나누는 함수 (임베딩 전 단계 전체)
+    pub fn extract(&self, py: Python<'_>, hit: &FileHit, loader: Loader) -> PyResult<FileChunks> {
+        let documents = self.load_documents(py, hit, loader)?;
+        let (pages, ocr, caption) = self.describe_documents(py, &documents)?;
+        let chunks = self.split_documents(py, &documents)?;
+        let len = chunks.bind(py).len()?;
+
+        Ok(FileChunks {
+            chunks,
+            len,
+            pages,
+            ocr,
+            caption,
+        })
+    }
+}
+
+/// 임베딩 모델과 `Vector Store`로 문서 조각을 저장, 삭제, 검색하는 `Python` 함수들 (`src-python/store.py`)
+pub struct Embedder {
+    embed_documents_func: Py<PyAny>,
+    embed_batch_func: Py<PyAny>,
+    store_documents_func: Py<PyAny>,
+    remove_embed_func: Py<PyAny>,
+    flush_func: Py<PyAny>,
+    search_func: Py<PyAny>,
+    sources_func: Py<PyAny>,
+}
+
+impl Embedder {
+    /// `config`로 임베딩 모델과 `Vector Store`를 불러와 `Embedder`를 생성하는 함수
+    pub fn load(py: Python<'_>, config: &Config) -> PyResult<Self> {
+        register_config(py, config)?;
+        PyModule::from_code_bound(
+            py,
+            include_str!("../src-python/core/vector_store.py"),
+            "vector_store.py",
+            "core.vector_store",
+        )?;
+        let module = PyModule::from_code_bound(
+            py,
+            include_str!("../src-python/store.py"),
+            "store.py",
+            "store",
+        )?;
+
+        Ok(Self {
+            embed_documents_func: module.getattr("embed_documents")?.into(),
+            embed_batch_func: module.getattr("embed_batch")?.into(),
+            store_documents_func: module.getattr("store_documents")?.into(),
+            remove_embed_func: module.getattr("remove_embed")?.into(),
+            flush_func: module.getattr("flush")?.into(),
+            search_func: module.getattr("search")?.into(),
+            sources_func: module.getattr("sources")?.into(),
+        })
+    }
+
+    /// 문서 조각들의 임베딩을 계산하는 함수
+    pub fn embed_documents(&self, py: Python<'_>, chunks: &Py<PyAny>) -> PyResult<Py<PyAny>> {
+        self.embed_documents_func.call1(py, (chunks,))
+    }
+
+    /// 여러 파일의 문서 조각 목록을 한 번에 임베딩하여 파일별 임베딩 목록을 반환하는 함수
+    pub fn embed_batch(&self, py: Python<'_>, files: &[&Py<PyAny>]) -> PyResult<Vec<Py<PyAny>>> {
+        let files = PyList::new_bound(py, files.iter().map(|chunks| chunks.clone_ref(py)));
+        self.embed_batch_func.call1(py, (files,))?.extract(py)
+    }
+
+    /// 문서 조각과 임베딩을 `Vector Store`에 저장하고, 저장된 문서 조각의 ID를 반환하는 함수
+    pub fn store_documents(
+        &self,
+        py: Python<'_>,
+        hit: &FileHit,
+        chunks: &Py<PyAny>,
+        embeddings: &Py<PyAny>,
+    ) -> PyResult<Vec<String>> {
+        self.store_documents_func
+            .call1(py, (hit.path.to_string_lossy(), chunks, embeddings))?
+            .extract(py)
+    }
+
+    /// `path` 파일 (디렉토리인 경우 하위 파일 전체)에서 생성된 문서들을 `Vector Store`에서 삭제하고,
+    /// 삭제된 문서 조각 수를 반환하는 함수
+    pub fn remove(&self, py: Python<'_>, path: &std::path::Path) -> PyResult<usize> {
+        let arg = PyTuple::new_bound(py, &[path.to_string_lossy()]);
+        self.remove_embed_func.call1(py, arg)?.extract(py)
+    }
+
+    /// `store_documents`와 `remove`로 바뀐 내용을 `Vector Store` 파일에 저장하는 함수
+    ///
+    /// 저장과 삭제는 메모리에만 반영되므로, `Manifest`를 저장하기 전에 호출해야 한다.
+    pub fn flush(&self, py: Python<'_>) -> PyResult<()> {
+        self.flush_func.call0(py)?;
+        Ok(())
+    }
+
+    /// `query`와 관련도가 높은 문서 조각을 최대 `k`개 검색하는 함수 (관련도 내림차순)
+    pub fn search(&self, py: Python<'_>, query: &str, k: usize) -> PyResult<Vec<RetrievedChunk>> {
+        let chunks: Vec<SearchResult> = self.search_func.call1(py, (query, k))?.extract(py)?;
+        Ok(chunks
+            .into_iter()
+            .map(|(content, path, score, page, offset)| RetrievedChunk {
+                content,
+                path: PathBuf::from(path),
+                score,
+                page,
+                offset,
+            })
+            .collect())
+    }
+
+    /// `Vector Store`에 저장된 파일 경로와 파일별 문서 조각 수를 가져오는 함수
+    pub fn sources(&self, py: Python<'_>) -> PyResult<Vec<(PathBuf, usize)>> {
+        let sources: Vec<(String, usize)> = self.sources_func.call0(py)?.extract(py)?;
+        Ok(sources
+            .into_iter()
+            .map(|(path, count)| (PathBuf::from(path), count))
+            .collect())
+    }
+}
+
+/// 검색된 문서로 질문에 답하는 LLM `Python` 함수
+///
+/// 모델을 불러오는 데 시간이 오래 걸리므로 답변이 필요할 때 가져온다.
+pub struct Answerer {
+    answer_func: Py<PyAny>,
+}
+
+impl Answerer {
+    pub fn load(py: Python<'_>, config: &Config) -> PyResult<Self> {
+        register_config(py, config)?;
+        let module = PyModule::from_code_bound(
+            py,
+            include_str!("../src-python/query.py"),
+            "query.py",
+            "query",
+        )?;
+
+        Ok(Self {
+            answer_func: module.getattr("answer")?.into(),
+        })
+    }
+
+    /// `chunks`를 참고 문서로 `question`에 대한 답변을 생성하는 함수
+    pub fn answer(
+        &self,
+        py: Python<'_>,
+        question: &str,
+        chunks: &[RetrievedChunk],
+    ) -> PyResult<String> {
+        let documents = chunks
+            .iter()
+            .map(|chunk| (chunk.content.as_str(), chunk.path.to_string_lossy()))
+            .collect::<Vec<_>>();
+        self.answer_func
+            .call1(py, (question, documents))?
+            .extract(py)
+    }
+}
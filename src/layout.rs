@@ -0,0 +1,253 @@
+/// 같은 줄로 보는 기준선 높이 차이 (글자 크기 대비)
+const LINE_TOLERANCE: f32 = 0.5;
+/// 같은 줄에서 띄어쓰기를 넣는 글자열 사이 간격 (글자 크기 대비)
+const WORD_GAP: f32 = 0.15;
+/// 같은 줄에서 다른 단의 글자로 보는 글자열 사이 간격 (글자 크기 대비)
+const SEGMENT_GAP: f32 = 1.0;
+/// 단 사이로 보는 최소 빈 공간 너비 (글자 크기 중앙값 대비)
+const COLUMN_GAP: f32 = 1.0;
+/// 문단 사이로 보는 기준선 간격 (글자 크기 대비)
+const PARAGRAPH_GAP: f32 = 1.6;
+
+/// 페이지 좌표(왼쪽 아래가 원점)에 놓인 글자열
+///
+/// 글자 출력 명령 하나(`Tj`, `TJ`)로 출력된 글자들이다.
+#[derive(Debug, Clone)]
+pub struct TextRun {
+    /// 첫 글자의 기준선 왼쪽 위치
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    /// 페이지 좌표에서의 글자 크기
+    pub size: f32,
+    pub text: String,
+}
+
+/// 같은 기준선에서 가까이 놓인 글자열들을 이은 줄
+#[derive(Debug, Clone)]
+struct Line {
+    /// 위에서부터의 줄 순서 (같은 높이에서 단으로 나뉜 줄들은 같은 값)
+    row: usize,
+    left: f32,
+    right: f32,
+    y: f32,
+    size: f32,
+    text: String,
+}
+
+impl Line {
+    fn new(row: usize, run: TextRun) -> Self {
+        Self {
+            row,
+            left: run.x,
+            // 간격 조정값이 큰 `TJ`는 폭이 음수일 수 있음
+            right: run.x + run.width.max(0.0),
+            y: run.y,
+            size: run.size,
+            text: run.text,
+        }
+    }
+
+    /// 글자열을 줄 끝에 덧붙이는 함수 (간격이 넓으면 띄어쓰기를 넣음)
+    fn push(&mut self, run: TextRun) {
+        let gap = run.x - self.right;
+        if gap > WORD_GAP * self.size.max(run.size)
+            && !self.text.ends_with(char::is_whitespace)
+            && !run.text.starts_with(char::is_whitespace)
+        {
+            self.text.push(' ');
+        }
+        self.text.push_str(&run.text);
+        self.right = self.right.max(run.x + run.width.max(0.0));
+        self.size = self.size.max(run.size);
+    }
+
+    fn top(&self) -> f32 {
+        self.y + self.size
+    }
+}
+
+/// 페이지의 글자열들을 사람이 읽는 순서의 글자로 만드는 함수
+///
+/// 글자열을 줄로 묶은 후, 단 사이의 빈 공간으로 페이지를 나누어(XY-cut) 왼쪽 단부터 위에서 아래로
+/// 읽는다. 나뉜 영역 안에서는 줄 간격이 넓은 곳을 문단으로 나누며, 영역과 문단 사이에는 빈 줄을 넣는다.
+pub fn reading_order(runs: Vec<TextRun>) -> String {
+    let mut blocks = vec![];
+    cut_blocks(group_lines(runs), &mut blocks);
+
+    blocks
+        .into_iter()
+        .map(paragraphs)
+        .filter(|text| !text.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// 기준선 높이가 비슷한 글자열들을 줄로 묶는 함수
+///
+/// 같은 높이라도 간격이 `SEGMENT_GAP`보다 넓으면 다른 단의 줄로 나눈다.
+fn group_lines(mut runs: Vec<TextRun>) -> Vec<Line> {
+    runs.retain(|run| !run.text.trim().is_empty());
+    runs.sort_by(|a, b| b.y.total_cmp(&a.y));
+
+    let mut rows: Vec<Vec<TextRun>> = vec![];
+    for run in runs {
+        match rows.last_mut() {
+            Some(row) if (row[0].y - run.y).abs() <= LINE_TOLERANCE * row[0].size.max(run.size) => {
+                row.push(run)
+            }
+            _ => rows.push(vec![run]),
+        }
+    }
+
+    let mut lines = vec![];
+    for (index, mut row) in rows.into_iter().enumerate() {
+        row.sort_by(|a, b| a.x.total_cmp(&b.x));
+        let mut line: Option<Line> = None;
+        for run in row {
+            match &mut line {
+                Some(current)
+                    if run.x - current.right <= SEGMENT_GAP * current.size.max(run.size) =>
+                {
+                    current.push(run)
+                }
+                _ => lines.extend(line.replace(Line::new(index, run))),
+            }
+        }
+        lines.extend(line);
+    }
+
+    lines
+}
+
+/// 줄들을 단과 가로 띠로 나누어 읽는 순서대로 `blocks`에 추가하는 함수
+///
+/// 전체를 세로로 가로지르는 빈 공간이 있으면 단으로 나누고, 없으면 위에서부터 가로 띠로 나눈다.
+/// 단이 있는 줄들과 없는 줄들(제목 등)은 다른 띠가 되며, 단이 없는 띠가 하나의 영역이 된다.
+fn cut_blocks(lines: Vec<Line>, blocks: &mut Vec<Vec<Line>>) {
+    if let Some((left, right)) = split_columns(&lines) {
+        cut_blocks(left, blocks);
+        cut_blocks(right, blocks);
+        return;
+    }
+
+    let bands = split_bands(lines);
+    if bands.len() == 1 {
+        blocks.extend(bands);
+        return;
+    }
+    for band in bands {
+        cut_blocks(band, blocks);
+    }
+}
+
+/// 페이지 전체를 세로로 가로지르는 빈 공간으로 글자열들을 왼쪽 단부터 나누는 함수
+///
+/// 가로 띠로는 나누지 않으므로, 본문 사이에 놓인 표는 한 덩어리로 남는다.
+pub fn column_blocks(runs: Vec<TextRun>) -> Vec<Vec<TextRun>> {
+    let Some(cut) = column_cut(&group_lines(runs.clone())) else {
+        return vec![runs];
+    };
+    let (left, right): (Vec<_>, Vec<_>) = runs
+        .into_iter()
+        .partition(|run| run.x + run.width.max(0.0) <= cut);
+
+    let mut blocks = column_blocks(left);
+    blocks.extend(column_blocks(right));
+    blocks
+}
+
+/// 세로로 가로지르는 빈 공간이 가장 왼쪽에 있는 곳에서 줄들을 (왼쪽, 오른쪽)으로 나누는 함수
+fn split_columns(lines: &[Line]) -> Option<(Vec<Line>, Vec<Line>)> {
+    let cut = column_cut(lines)?;
+    Some(lines.iter().cloned().partition(|line| line.right <= cut))
+}
+
+/// 세로로 가로지르는 빈 공간이 가장 왼쪽에 있는 곳의 x 좌표 (빈 공간 왼쪽 줄들의 오른쪽 끝)
+fn column_cut(lines: &[Line]) -> Option<f32> {
+    let mut sizes = lines.iter().map(|line| line.size).collect::<Vec<_>>();
+    sizes.sort_by(f32::total_cmp);
+    let min_gap = COLUMN_GAP * sizes.get(sizes.len() / 2).copied().unwrap_or_default();
+
+    let mut spans = lines
+        .iter()
+        .map(|line| (line.left, line.right))
+        .collect::<Vec<_>>();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut right = spans.first()?.1;
+    let cut = spans.iter().skip(1).find_map(|&(start, end)| {
+        if start - right >= min_gap {
+            return Some(right);
+        }
+        right = right.max(end);
+        None
+    })?;
+
+    // 한쪽이 비면 같은 줄들을 계속 나누게 되므로 나누지 않음
+    let left = lines.iter().filter(|line| line.right <= cut).count();
+    if left == 0 || left == lines.len() {
+        return None;
+    }
+
+    Some(cut)
+}
+
+/// 줄들을 세로로 겹치는 줄끼리 묶은 후, 단으로 나뉘는지 여부가 같은 묶음끼리 이은 가로 띠로 나누는 함수
+fn split_bands(mut lines: Vec<Line>) -> Vec<Vec<Line>> {
+    lines.sort_by(|a, b| b.top().total_cmp(&a.top()));
+
+    // 묶음의 가장 낮은 기준선보다 위쪽이 높은 줄은 같은 묶음
+    let mut groups: Vec<Vec<Line>> = vec![];
+    let mut bottom = f32::INFINITY;
+    for line in lines {
+        match groups.last_mut() {
+            Some(group) if line.top() > bottom => {
+                bottom = bottom.min(line.y);
+                group.push(line);
+            }
+            _ => {
+                bottom = line.y;
+                groups.push(vec![line]);
+            }
+        }
+    }
+
+    let mut bands: Vec<Vec<Line>> = vec![];
+    for group in groups {
+        if let Some(band) = bands.last_mut() {
+            let mut merged = band.clone();
+            merged.extend(group.iter().cloned());
+            let columns = split_columns(&merged).is_some();
+            if columns || (split_columns(band).is_none() && split_columns(&group).is_none()) {
+                *band = merged;
+                continue;
+            }
+        }
+        bands.push(group);
+    }
+
+    bands
+}
+
+/// 한 영역의 줄들을 위에서부터 이어 붙이는 함수 (줄 간격이 넓은 곳은 문단으로 나눔)
+fn paragraphs(mut lines: Vec<Line>) -> String {
+    lines.sort_by(|a, b| a.row.cmp(&b.row).then(a.left.total_cmp(&b.left)));
+
+    let mut text = String::new();
+    let mut previous: Option<&Line> = None;
+    for line in &lines {
+        if let Some(previous) = previous {
+            if previous.row == line.row {
+                text.push(' ');
+            } else if previous.y - line.y > PARAGRAPH_GAP * previous.size.max(line.size) {
+                text.push_str("\n\n");
+            } else {
+                text.push('\n');
+            }
+        }
+        text.push_str(line.text.trim());
+        previous = Some(line);
+    }
+
+    text
+}
@@ -0,0 +1,81 @@
+use std::{io::Read, path::Path};
+
+/// 파일 내용을 판별하기 위해 읽는 앞부분의 크기 (byte)
+const SNIFF_LENGTH: usize = 1024;
+
+/// MIME 타입별로 문서를 추출하는 로더
+///
+/// 여기에 없는 MIME 타입의 파일은 저장하지 않는다 (`FileStatus::Unsupported`).
+const LOADERS: &[(&str, Loader)] = &[
+    ("application/pdf", Loader::Pdf),
+    ("image/png", Loader::Image),
+    ("image/jpeg", Loader::Image),
+    ("image/tiff", Loader::Image),
+    ("image/bmp", Loader::Image),
+    ("image/gif", Loader::Image),
+    ("image/webp", Loader::Image),
+];
+
+/// 파일에서 문서 목록을 추출하는 `Python` 로더 (`src-python/main.py`의 `LOADERS`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Loader {
+    /// `pdf_reader`로 페이지별 글자와 표를 추출 (읽지 못하면 PDFPlumber)
+    Pdf,
+    /// 이미지 캡션(BLIP)과 OCR(Tesseract)로 글자를 추출
+    Image,
+}
+
+impl Loader {
+    /// `mime` 타입의 파일을 처리할 로더를 찾는 함수 (지원하지 않으면 `None`)
+    pub fn for_mime(mime: &str) -> Option<Self> {
+        LOADERS
+            .iter()
+            .find(|(loader_mime, _)| *loader_mime == mime)
+            .map(|(_, loader)| *loader)
+    }
+
+    /// `src-python/main.py`의 `LOADERS`에 등록된 이름
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Image => "image",
+        }
+    }
+}
+
+/// 파일 내용으로 MIME 타입을 판별하는 함수
+///
+/// 확장자가 대문자이거나 잘못 붙은 파일도 내용에 맞는 로더로 처리하기 위해 사용한다.
+/// 내용으로 판별할 수 없으면 확장자로 추정한 `guessed`를 사용하되, 지원하는 형식으로 추정된
+/// 파일은 내용이 그 형식이 아니므로 `application/octet-stream`으로 판별한다.
+pub fn detect_mime(path: &Path, guessed: &'static str) -> std::io::Result<&'static str> {
+    let mut header = Vec::with_capacity(SNIFF_LENGTH);
+    std::fs::File::open(path)?
+        .take(SNIFF_LENGTH as u64)
+        .read_to_end(&mut header)?;
+
+    let mime = match sniff_mime(&header) {
+        Some(mime) => mime,
+        None if Loader::for_mime(guessed).is_some() => "application/octet-stream",
+        None => guessed,
+    };
+
+    Ok(mime)
+}
+
+/// 파일의 앞부분(`header`)에 있는 형식 서명으로 MIME 타입을 판별하는 함수
+fn sniff_mime(header: &[u8]) -> Option<&'static str> {
+    let mime = match header {
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', ..] => "image/png",
+        [0xff, 0xd8, 0xff, ..] => "image/jpeg",
+        [b'I', b'I', b'*', 0, ..] | [b'M', b'M', 0, b'*', ..] => "image/tiff",
+        [b'B', b'M', ..] => "image/bmp",
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => "image/gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+        // PDF 헤더는 파일 앞부분 1024 byte 안에 있으면 됨
+        _ if header.windows(5).any(|window| window == b"%PDF-") => "application/pdf",
+        _ => return None,
+    };
+
+    Some(mime)
+}
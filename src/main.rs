@@ -1,134 +1,623 @@
-use std::io::Write;
-
-use pyo3::{
-    types::{PyAnyMethods, PyModule, PyTuple},
-    Py, PyAny, PyResult, Python,
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
 };
 
+use checkpoint::Checkpoint;
+use clap::Parser;
+use cli::{Cli, Command, IndexArgs, OutputFormat, QueryArgs};
+use config::Config;
+use crawler::CrawlOptions;
+use discovery::{DiscoveryPages, DiscoverySource};
+use embed::{format_py_error, Answerer, Embedder, Models, RetrievedChunk, LOADER_VERSION};
+use manifest::Manifest;
+use native::{CancelToken, FileHit, QueryMode, SearchFilter, SearchOptions};
+use progress::TerminalProgress;
+use pyo3::Python;
+use report::{FileReport, FileStatus, IngestReport};
+use serde_json::json;
+use watch::{FileChange, WatchOptions};
+
+type CommandResult = Result<(), Box<dyn Error>>;
+
 #[tokio::main]
 async fn main() {
-    // 1. 쿼리 가져오기
-    let args: Vec<String> = std::env::args().collect();
-    let query = args.get(1).map_or(String::from("."), |v| v.to_string());
-    println!("File Query: {query} (Default: .)");
-
-    // 2. 쿼리에 해당하는 파일들을 가져오기
-    let files =
-        native::search_local_files_by_query(query.to_string()).expect("Failed to search files");
-    let files = files
-        .iter()
-        // 2-1. 존재하는 파일만 필터링
-        .filter(|(_, path, _)| {
-            std::path::Path::new(path.strip_prefix("file:").unwrap_or(path)).exists()
-        })
-        .collect::<Vec<_>>();
+    let cli = Cli::parse();
+    let config = match cli.load_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", error_report(&e));
+            std::process::exit(1);
+        }
+    };
 
-    // 3. 이미지 캡션 모델 사전 로드
-    if let Some((_, path, _)) = files
-        .iter()
-        .filter(|(_, path, _)| path.ends_with(".jpg") || path.ends_with(".png"))
-        .collect::<Vec<_>>()
-        .first()
-    {
-        let _: PyResult<()> = Python::with_gil(move |py| {
-            let initalize_module = PyModule::from_code_bound(
-                py,
-                include_str!("../src-python/core/blip/blip_model.py"),
-                "blip_model.py",
-                "blip_model",
-            )?;
-            let initialize_vision_model: Py<PyAny> =
-                initalize_module.getattr("initialize_vision_model")?.into();
-            let initialize_text_decoder: Py<PyAny> =
-                initalize_module.getattr("initialize_text_decoder")?.into();
-
-            let path = path.strip_prefix("file:").unwrap_or(path);
-
-            let arg = PyTuple::new_bound(py, &[path]);
-            initialize_vision_model.call1(py, arg)?;
-            initialize_text_decoder.call0(py)?;
-            Ok(())
-        });
-    }
-
-    let time = std::time::SystemTime::now();
-    // 4. 파일들을 `Python`으로 전달하여 `Vector Store`에 저장
-    let success_length: PyResult<usize> = Python::with_gil(|py| {
-        PyModule::from_code_bound(
-            py,
-            include_str!("../src-python/core/blip/blip_model.py"),
-            "blip_model.py",
-            "core.blip.blip_model",
-        )?;
-        PyModule::from_code_bound(
-            py,
-            include_str!("../src-python/core/blip/blip.py"),
-            "blip.py",
-            "core.blip.blip",
-        )?;
-        PyModule::from_code_bound(
-            py,
-            include_str!("../src-python/core/loader/pdf.py"),
-            "pdf.py",
-            "core.loader.pdf",
-        )?;
-        PyModule::from_code_bound(
-            py,
-            include_str!("../src-python/core/loader/image.py"),
-            "image.py",
-            "core.loader.image",
-        )?;
-        PyModule::from_code_bound(
-            py,
-            include_str!("../src-python/core/vector_store.py"),
-            "vector_store.py",
-            "core.vector_store",
-        )?;
-        let module = PyModule::from_code_bound(
-            py,
-            include_str!("../src-python/main.py"),
-            "main.py",
-            "main",
-        )?;
-        let pdf_embed_func: Py<PyAny> = module.getattr("pdf_embed")?.into();
-        let image_embed_func: Py<PyAny> = module.getattr("image_embed")?.into();
-
-        let success_list = files
-            .iter()
-            .filter(|(name, path, _)| {
-                let path = path.strip_prefix("file:").unwrap_or(path);
-                let arg = PyTuple::new_bound(py, &[path]);
-                println!("Parsing {}", name);
-                if path.ends_with(".pdf") {
-                    pdf_embed_func.call1(py, arg)
-                } else {
-                    image_embed_func.call1(py, arg)
+    // `index --dry-run`은 모델을 불러오지 않으므로, 결과를 한 번에 보여주기 전에 OS 인덱스 검색을
+    // 런타임의 블로킹 스레드에서 먼저 실행
+    let prefetched = match &cli.command {
+        Some(Command::Index(args)) if args.dry_run && !args.resume => prefetch_os_index(args).await,
+        _ => None,
+    };
+
+    let result = Python::with_gil(|py| {
+        let config = &config;
+        let models = Models::default();
+        let format = cli.format;
+        match &cli.command {
+            Some(Command::Index(args)) => {
+                run_index(py, config, &models, args, prefetched.as_deref(), format)
+            }
+            Some(Command::Query(args)) => run_query(py, config, &models, args, format),
+            Some(Command::Ask(args)) => run_ask(py, config, &models, args, format),
+            Some(Command::Status) => run_status(py, config, &models, format),
+            Some(Command::Purge { path }) => run_purge(py, config, &models, path, format),
+            // 하위 명령어가 없으면 `Vector Store`에 저장된 파일들을 대화형으로 검색
+            None => repl::run(py, config, &models, |py, models| {
+                let args = IndexArgs::default();
+                let (query, filter, crawl_options, search_options) = discovery_options(&args);
+                let discover = |source| {
+                    discover_pages(
+                        &query,
+                        source,
+                        &filter,
+                        &crawl_options,
+                        &search_options,
+                        None,
+                    )
+                };
+                let mut manifest = load_manifest(config);
+                let cancel = search_options.cancel.clone();
+                match ingest(
+                    py,
+                    config,
+                    models,
+                    &mut manifest,
+                    &discover,
+                    args.source,
+                    &cancel,
+                ) {
+                    Ok(report) => {
+                        report.print_table();
+                        println!("Committing {}", report.summary());
+                    }
+                    Err(e) => println!("Failed to reindex: {}", error_report(&*e)),
                 }
-                .is_ok()
             })
+            .map_err(Into::into),
+        }
+    });
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", error_report(&*e));
+        std::process::exit(1);
+    }
+}
+
+/// `index` 명령어의 OS 인덱스 검색 결과를 미리 모두 가져오는 함수 (`native::search_local_files_async`)
+///
+/// 실패하면 `None`을 반환하며, 이후 검색 단계에서 다시 검색하여 다른 소스로 대체한다.
+async fn prefetch_os_index(args: &IndexArgs) -> Option<Vec<FileHit>> {
+    if args.source == DiscoverySource::Crawl {
+        return None;
+    }
+    let (query, filter, _, search_options) = discovery_options(args);
+    native::search_local_files_async(query, filter, search_options)
+        .await
+        .ok()
+}
+
+/// `index` 명령어: 파일을 검색하여 `Vector Store`에 저장하고, `--watch`인 경우 변경 사항을 계속 반영
+///
+/// Ctrl-C를 누르면 처리 중인 파일까지 저장한 후 `Checkpoint`를 기록하고 종료한다.
+/// `--resume`으로 실행하면 기록된 검색 조건으로 이어서 저장한다.
+fn run_index(
+    py: Python<'_>,
+    config: &Config,
+    models: &Models,
+    args: &IndexArgs,
+    prefetched: Option<&[FileHit]>,
+    format: OutputFormat,
+) -> CommandResult {
+    let store_dir = &config.store.faiss_path;
+    let checkpoint = Checkpoint::load(store_dir)?;
+    let resumed_args;
+    let args = match (&checkpoint, args.resume) {
+        (Some(checkpoint), true) => {
+            eprintln!(
+                "Resuming cancelled run ({} files were processed)",
+                checkpoint.processed
+            );
+            resumed_args = checkpoint.resume_args(args);
+            &resumed_args
+        }
+        (None, true) => return Err("no cancelled index run to resume".into()),
+        (Some(_), false) => {
+            eprintln!("A previous run was cancelled; use `index --resume` to continue it");
+            args
+        }
+        (None, false) => args,
+    };
+
+    let (query, filter, crawl_options, search_options) = discovery_options(args);
+    // 홈 디렉토리 전체를 감시하면 inotify 감시 개수 제한을 넘거나 관련 없는 파일을 저장하게 됨
+    if args.watch && filter.path_prefixes.is_empty() {
+        return Err("`--watch` requires at least one directory to watch in the targets".into());
+    }
+    eprintln!("File Query: {query}");
+    eprintln!("Discovery Source: {:?}", args.source);
+    eprintln!("Query Mode: {:?}", search_options.mode);
+    let discover = |source| {
+        discover_pages(
+            &query,
+            source,
+            &filter,
+            &crawl_options,
+            &search_options,
+            prefetched,
+        )
+    };
+
+    let cancel = search_options.cancel.clone();
+    cancel_on_ctrl_c(cancel.clone());
+    let time = std::time::Instant::now();
+    let mut manifest = load_manifest(config);
+    if args.dry_run {
+        let preview = pipeline::preview(config, &manifest, &discover, args.source, &cancel);
+        match format {
+            OutputFormat::Text => {
+                preview.print_table();
+                println!("Elapsed Time: {:?}", time.elapsed());
+                println!("{}", preview.summary());
+            }
+            OutputFormat::Json => println!("{}", preview.to_json()),
+        }
+        if let Some(path) = &args.report {
+            write_report(path, &preview.to_json())?;
+        }
+        return Ok(());
+    }
+
+    let report = ingest(
+        py,
+        config,
+        models,
+        &mut manifest,
+        &discover,
+        args.source,
+        &cancel,
+    )?;
+    let elapsed = time.elapsed();
+    if report.cancelled {
+        Checkpoint::new(args, report.files.len()).save(store_dir)?;
+        eprintln!("Cancelled; run `index --resume` to continue");
+    } else {
+        Checkpoint::clear(store_dir)?;
+    }
+    match format {
+        OutputFormat::Text => {
+            report.print_table();
+            println!("Elapsed Time: {elapsed:?}");
+            println!("Committing {}", report.summary());
+        }
+        OutputFormat::Json => println!("{}", report.to_json(elapsed)),
+    }
+    if let Some(path) = &args.report {
+        write_report(path, &report.to_json(elapsed))?;
+    }
+
+    if args.watch && !report.cancelled {
+        let watch_options = WatchOptions {
+            roots: filter.path_prefixes.clone(),
+            cancel,
+            ..Default::default()
+        };
+        eprintln!("Watching {:?} for changes...", watch_options.roots);
+        watch::watch(&watch_options, &filter, |changes| {
+            apply_changes(py, config, models, &mut manifest, changes)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// `query` 명령어: 질문과 관련된 문서 조각을 출력
+fn run_query(
+    py: Python<'_>,
+    config: &Config,
+    models: &Models,
+    args: &QueryArgs,
+    format: OutputFormat,
+) -> CommandResult {
+    let embedder = models.embedder(py, config)?;
+    let chunks = embedder.search(py, &args.text, args.top_k)?;
+    match format {
+        OutputFormat::Text => repl::print_chunks(&chunks),
+        OutputFormat::Json => println!("{}", chunks_to_json(&chunks)),
+    }
+
+    Ok(())
+}
+
+/// `ask` 명령어: 질문과 관련된 문서 조각으로 LLM 답변을 생성하여 출력
+fn run_ask(
+    py: Python<'_>,
+    config: &Config,
+    models: &Models,
+    args: &QueryArgs,
+    format: OutputFormat,
+) -> CommandResult {
+    let embedder = models.embedder(py, config)?;
+    let chunks = embedder.search(py, &args.text, args.top_k)?;
+    // 관련된 문서가 없으면 LLM을 불러오지 않음
+    let answer = if chunks.is_empty() {
+        None
+    } else {
+        Some(Answerer::load(py, config)?.answer(py, &args.text, &chunks)?)
+    };
+
+    match format {
+        OutputFormat::Text => {
+            repl::print_chunks(&chunks);
+            if let Some(answer) = &answer {
+                println!("Answer: {}", answer.trim());
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            json!({
+                "answer": answer.as_deref().map(str::trim),
+                "chunks": chunks_to_json(&chunks),
+            })
+        ),
+    }
+
+    Ok(())
+}
+
+/// `status` 명령어: `Vector Store`에 저장된 파일과 문서 조각 수를 출력
+fn run_status(
+    py: Python<'_>,
+    config: &Config,
+    models: &Models,
+    format: OutputFormat,
+) -> CommandResult {
+    let embedder = models.embedder(py, config)?;
+    let sources = embedder.sources(py)?;
+    let total_chunks = sources.iter().map(|(_, count)| count).sum::<usize>();
+    match format {
+        OutputFormat::Text => {
+            println!(
+                "Store: {:?} ({})",
+                config.store.backend,
+                config.store.faiss_path.display()
+            );
+            for (path, count) in &sources {
+                println!("{count:>5} chunks  {}", path.display());
+            }
+            println!("{} files, {total_chunks} chunks", sources.len());
+        }
+        OutputFormat::Json => {
+            let files = sources
+                .iter()
+                .map(|(path, count)| json!({ "path": path, "chunks": count }))
+                .collect::<Vec<_>>();
+            println!(
+                "{}",
+                json!({
+                    "store": format!("{:?}", config.store.backend).to_lowercase(),
+                    "store_path": config.store.faiss_path,
+                    "files": files,
+                    "total_files": sources.len(),
+                    "total_chunks": total_chunks,
+                })
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// `purge` 명령어: 파일 (디렉토리인 경우 하위 파일 전체)의 문서를 `Vector Store`에서 삭제
+fn run_purge(
+    py: Python<'_>,
+    config: &Config,
+    models: &Models,
+    path: &Path,
+    format: OutputFormat,
+) -> CommandResult {
+    // 저장된 경로는 절대 경로이므로 변환 (이미 삭제된 파일은 그대로 사용)
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let embedder = models.embedder(py, config)?;
+    let removed = embedder.remove(py, &path)?;
+    let mut manifest = load_manifest(config);
+    manifest.remove(&path);
+    pipeline::flush(Some(embedder), &mut manifest).map_err(|e| e as Box<dyn Error>)?;
+    match format {
+        OutputFormat::Text => println!("Removed {removed} chunks of {}", path.display()),
+        OutputFormat::Json => println!("{}", json!({ "path": path, "removed_chunks": removed })),
+    }
+
+    Ok(())
+}
+
+/// `index` 명령어의 인자로 검색어와 검색 조건들을 만드는 함수
+///
+/// 존재하는 디렉토리는 절대 경로로 바꾸어 검색 범위로 사용하고, 나머지 인자는 공백으로 이어서 검색어로 사용한다.
+/// OS 인덱스와 `Manifest`는 절대 경로를 사용하므로, 상대 경로로는 결과를 거르거나 삭제된 파일을 찾을 수 없다.
+fn discovery_options(args: &IndexArgs) -> (String, SearchFilter, CrawlOptions, SearchOptions) {
+    let (roots, words): (Vec<&String>, Vec<&String>) = args
+        .targets
+        .iter()
+        .partition(|target| Path::new(target).is_dir());
+    let query = if words.is_empty() {
+        String::from(".")
+    } else {
+        words
+            .iter()
+            .map(|word| word.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut filter = SearchFilter::default();
+    let mut crawl_options = CrawlOptions {
+        max_results: args.limit,
+        ..Default::default()
+    };
+    if !roots.is_empty() {
+        let roots = roots
+            .into_iter()
+            .map(|root| std::fs::canonicalize(root).unwrap_or_else(|_| PathBuf::from(root)))
             .collect::<Vec<_>>();
+        filter.path_prefixes = roots.clone();
+        crawl_options.roots = roots;
+    }
+    let search_options = SearchOptions {
+        limit: args.limit,
+        mode: if args.content {
+            QueryMode::Content
+        } else {
+            QueryMode::FileName
+        },
+        ..Default::default()
+    };
+
+    (query, filter, crawl_options, search_options)
+}
+
+/// 파일 검색 결과를 페이지 단위로 가져오는 함수
+///
+/// 검색을 시작하지 못한 경우도 첫 페이지의 오류로 반환한다.
+fn discover_pages(
+    query: &str,
+    source: DiscoverySource,
+    filter: &SearchFilter,
+    crawl_options: &CrawlOptions,
+    search_options: &SearchOptions,
+    prefetched: Option<&[FileHit]>,
+) -> DiscoveryPages {
+    match discovery::discover_pages(
+        query,
+        source,
+        filter,
+        crawl_options,
+        search_options,
+        prefetched,
+    ) {
+        Ok(pages) => pages,
+        Err(e) => Box::new(std::iter::once(Err(e))),
+    }
+}
+
+/// `Vector Store` 디렉토리의 `Manifest`를 읽는 함수
+///
+/// 읽을 수 없으면 빈 목록으로 시작한다. 모든 파일을 다시 저장하게 되지만,
+/// 저장하기 전에 기존 문서를 삭제하므로 중복되지 않는다.
+fn load_manifest(config: &Config) -> Manifest {
+    Manifest::load(&config.store.faiss_path).unwrap_or_else(|e| {
+        eprintln!(
+            "Failed to load manifest, reindexing all files: {}",
+            error_report(&e)
+        );
+        Manifest::empty(&config.store.faiss_path)
+    })
+}
+
+/// `source`로 파일을 검색하여 바뀐 파일만 `Vector Store`에 저장하는 함수 (`pipeline::run`)
+///
+/// `manifest`에 기록된 파일 중 더 이상 존재하지 않는 파일은 `Vector Store`에서 삭제한다.
+fn ingest(
+    py: Python<'_>,
+    config: &Config,
+    models: &Models,
+    manifest: &mut Manifest,
+    discover: &(impl Fn(DiscoverySource) -> DiscoveryPages + Sync),
+    source: DiscoverySource,
+    cancel: &CancelToken,
+) -> Result<IngestReport, Box<dyn Error>> {
+    let mut progress = TerminalProgress::default();
+    let mut report = pipeline::run(
+        py,
+        config,
+        models,
+        manifest,
+        discover,
+        source,
+        cancel,
+        &mut |event, status| progress.report(event, status),
+    )
+    .map_err(|e| e as Box<dyn Error>)?;
+    // 취소된 경우 삭제된 파일은 이어서 실행할 때 확인
+    if report.cancelled {
+        return Ok(report);
+    }
+
+    let missing = manifest.missing();
+    if !missing.is_empty() {
+        let embedder = models.embedder(py, config)?;
+        for path in missing {
+            report.push(remove_file(py, embedder, manifest, path));
+        }
+    }
+    pipeline::flush(models.loaded_embedder(), manifest).map_err(|e| e as Box<dyn Error>)?;
+
+    Ok(report)
+}
 
-        Ok(success_list.len())
+/// `index --report`의 JSON 파일을 저장하는 함수
+fn write_report(path: &Path, json: &serde_json::Value) -> CommandResult {
+    let text = serde_json::to_string_pretty(json)?;
+    std::fs::write(path, text)
+        .map_err(|e| format!("failed to write report {}: {e}", path.display()))?;
+
+    Ok(())
+}
+
+/// Ctrl-C를 누르면 `cancel`을 취소하고, 한 번 더 누르면 바로 종료하도록 하는 함수
+///
+/// 취소된 작업은 처리 중인 파일을 마친 후 `Vector Store`와 `Manifest`를 저장하고 반환한다.
+fn cancel_on_ctrl_c(cancel: CancelToken) {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        eprintln!("Cancelling after the current file... (press Ctrl-C again to exit now)");
+        cancel.cancel();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(130);
+        }
     });
+}
+
+/// 삭제된 파일의 문서를 `Vector Store`와 `manifest`에서 삭제하는 함수
+fn remove_file(
+    py: Python<'_>,
+    embedder: &Embedder,
+    manifest: &mut Manifest,
+    path: PathBuf,
+) -> FileReport {
+    eprintln!("Removing {}", path.display());
+    let time = std::time::Instant::now();
+    let mut file = match embedder.remove(py, &path) {
+        Ok(removed) => {
+            manifest.remove(&path);
+            FileReport {
+                chunks: removed,
+                ..FileReport::new(path, FileStatus::Removed)
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to remove {}: {e}", path.display());
+            FileReport::failed(path, format_py_error(py, &e))
+        }
+    };
+    file.duration = time.elapsed();
 
-    if let Ok(duration) = time.elapsed() {
-        println!("Elapsed Time: {:?}", duration);
+    file
+}
+
+/// 검색된 문서 조각을 JSON 배열로 변환하는 함수
+fn chunks_to_json(chunks: &[RetrievedChunk]) -> serde_json::Value {
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(rank, chunk)| {
+            json!({
+                "rank": rank + 1,
+                "score": chunk.score,
+                "path": chunk.path,
+                "page": chunk.page,
+                "offset": chunk.offset,
+                "content": chunk.content,
+            })
+        })
+        .collect()
+}
+
+/// 오류와 그 원인들을 `: `로 이어 붙인 문자열을 만드는 함수
+fn error_report(error: &dyn Error) -> String {
+    let mut report = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        report.push_str(&format!(": {e}"));
+        source = e.source();
     }
+    report
+}
 
-    println!(
-        "Committing {} files...",
-        success_length.expect("Failed to embed files")
-    );
+/// 변경된 파일의 기존 문서를 삭제하고 다시 저장하는 함수 (내용이 바뀌지 않은 파일은 건너뜀)
+fn apply_changes(
+    py: Python<'_>,
+    config: &Config,
+    models: &Models,
+    manifest: &mut Manifest,
+    changes: Vec<FileChange>,
+) {
+    // 파일 내용으로 판별한 MIME 타입 (읽을 수 없는 파일은 오류로 처리)
+    let changes = changes
+        .into_iter()
+        .map(|change| match change {
+            FileChange::Upserted(mut hit) => {
+                let detected = pipeline::detect_mime(&mut hit);
+                (FileChange::Upserted(hit), detected)
+            }
+            removed => (removed, Ok(())),
+        })
+        .collect::<Vec<_>>();
+    let hits = changes
+        .iter()
+        .filter_map(|(change, _)| match change {
+            FileChange::Upserted(hit) => Some(hit.clone()),
+            FileChange::Removed(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let (document_loader, embedder) = match models.ingest(py, &hits, config) {
+        Ok(models) => models,
+        Err(e) => {
+            eprintln!("Failed to load models: {e}");
+            return;
+        }
+    };
 
-    // 5. `Vector Store`에 저장된 파일들을 검색
-    print!("Contents Query > ");
-    std::io::stdout().flush().unwrap();
-    let mut query = String::new();
-    std::io::stdin().read_line(&mut query).unwrap();
+    let mut report = IngestReport::default();
+    for (change, detected) in changes {
+        let file = match change {
+            FileChange::Upserted(hit) => {
+                let state = detected.and_then(|_| manifest.check(&hit, LOADER_VERSION));
+                match pipeline::changed_hash(manifest, &hit, state) {
+                    Ok(hash) => {
+                        pipeline::index_file(py, document_loader, embedder, manifest, &hit, hash)
+                    }
+                    Err(file) => file,
+                }
+            }
+            FileChange::Removed(path) => remove_file(py, embedder, manifest, path),
+        };
+        report.push(file);
+    }
+    report.print_table();
+    if let Err(e) = pipeline::flush(Some(embedder), manifest) {
+        eprintln!("{}", error_report(&*e));
+    }
 }
 
+mod checkpoint;
+mod cli;
+mod config;
+mod crawler;
+mod discovery;
+mod embed;
+mod layout;
+mod loader;
+mod manifest;
 mod native;
-// mod ocr;
-// mod pdf_reader;
+mod ocr;
+mod pdf_reader;
+mod pipeline;
+mod preview;
+mod progress;
+mod repl;
+mod report;
+mod table;
+mod watch;
 // mod text_store;
 // mod vector_store;
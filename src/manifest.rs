@@ -0,0 +1,260 @@
+use std::{
+    collections::BTreeMap,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::native::FileHit;
+
+/// `Vector Store` 디렉토리에 저장하는 목록 파일 이름
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// `Vector Store`에 저장된 파일들의 상태를 기록하는 목록
+///
+/// 파일 크기와 수정 시각이 같으면 변경되지 않은 것으로 보고,
+/// 다르면 내용의 해시를 비교하여 실제로 내용이 바뀐 파일만 다시 저장하게 한다.
+#[derive(Debug)]
+pub struct Manifest {
+    path: PathBuf,
+    entries: BTreeMap<PathBuf, ManifestEntry>,
+    /// 저장되지 않은 변경 사항이 있는지 여부
+    dirty: bool,
+}
+
+/// 저장된 파일 하나의 상태
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub size: u64,
+    pub modified: SystemTime,
+    /// 파일 내용의 SHA-256 (16진수)
+    pub hash: String,
+    /// 파일에서 생성된 문서 조각의 `Vector Store` ID
+    pub chunk_ids: Vec<String>,
+    /// 파일을 저장할 때 사용한 로더 버전 (`embed::LOADER_VERSION`)
+    pub loader_version: u32,
+}
+
+/// `Manifest`와 비교한 파일의 상태
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileState {
+    /// 목록에 없는 파일
+    New {
+        hash: String,
+    },
+    /// 내용 또는 로더 버전이 바뀐 파일
+    Modified {
+        hash: String,
+    },
+    /// 내용은 같고 크기나 수정 시각만 바뀐 파일 (복사, `touch` 등)
+    Touched,
+    Unchanged,
+}
+
+/// 여러 스레드에서 파일 상태를 확인하기 위한 `Manifest`의 읽기 전용 복사본
+#[derive(Debug, Clone)]
+pub struct ManifestSnapshot(Arc<BTreeMap<PathBuf, ManifestEntry>>);
+
+impl ManifestSnapshot {
+    /// `Manifest::check`와 같음
+    pub fn check(&self, hit: &FileHit, loader_version: u32) -> std::io::Result<FileState> {
+        check_entry(self.0.get(&hit.path), hit, loader_version)
+    }
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    /// (Error, Path)
+    Read(std::io::Error, PathBuf),
+    /// (Error, Path)
+    Parse(serde_json::Error, PathBuf),
+    /// (Error, Path)
+    Write(std::io::Error, PathBuf),
+}
+
+impl std::fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read(_, path) => write!(f, "failed to read manifest {}", path.display()),
+            Self::Parse(_, path) => write!(f, "invalid manifest {}", path.display()),
+            Self::Write(_, path) => write!(f, "failed to write manifest {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read(e, _) | Self::Write(e, _) => Some(e),
+            Self::Parse(e, _) => Some(e),
+        }
+    }
+}
+
+impl Manifest {
+    /// `store_dir`에 목록 파일이 없는 빈 `Manifest`를 만드는 함수
+    pub fn empty(store_dir: &Path) -> Self {
+        Self {
+            path: store_dir.join(MANIFEST_FILE_NAME),
+            entries: BTreeMap::new(),
+            dirty: false,
+        }
+    }
+
+    /// `store_dir`의 목록 파일을 읽는 함수 (파일이 없으면 빈 목록)
+    pub fn load(store_dir: &Path) -> Result<Self, ManifestError> {
+        let mut manifest = Self::empty(store_dir);
+        let text = match std::fs::read_to_string(&manifest.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(manifest),
+            Err(e) => return Err(ManifestError::Read(e, manifest.path)),
+        };
+        manifest.entries = serde_json::from_str(&text)
+            .map_err(|e| ManifestError::Parse(e, manifest.path.clone()))?;
+
+        Ok(manifest)
+    }
+
+    /// 변경 사항이 있으면 목록 파일에 저장하는 함수
+    ///
+    /// 저장 중에 종료되더라도 기존 파일이 깨지지 않도록 임시 파일에 쓴 후 교체한다.
+    pub fn save(&mut self) -> Result<(), ManifestError> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let write_error = |e| ManifestError::Write(e, self.path.clone());
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(write_error)?;
+        }
+        let text = serde_json::to_string_pretty(&self.entries)
+            .map_err(|e| write_error(std::io::Error::other(e)))?;
+        let temp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&temp_path, text).map_err(write_error)?;
+        std::fs::rename(&temp_path, &self.path).map_err(write_error)?;
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// `hit` 파일이 저장된 후 바뀌었는지 확인하는 함수
+    ///
+    /// 크기와 수정 시각이 기록과 다를 때만 파일을 읽어 해시를 계산한다.
+    pub fn check(&self, hit: &FileHit, loader_version: u32) -> std::io::Result<FileState> {
+        check_entry(self.entries.get(&hit.path), hit, loader_version)
+    }
+
+    /// `path` 파일의 기록을 가져오는 함수
+    pub fn get(&self, path: &Path) -> Option<&ManifestEntry> {
+        self.entries.get(path)
+    }
+
+    /// 현재 기록의 읽기 전용 복사본을 만드는 함수
+    pub fn snapshot(&self) -> ManifestSnapshot {
+        ManifestSnapshot(Arc::new(self.entries.clone()))
+    }
+
+    /// `FileState::Touched`인 파일의 크기와 수정 시각을 갱신하는 함수
+    pub fn touch(&mut self, hit: &FileHit) {
+        if let Some(entry) = self.entries.get_mut(&hit.path) {
+            entry.size = hit.size;
+            entry.modified = hit.modified;
+            self.dirty = true;
+        }
+    }
+
+    /// `hit` 파일을 저장한 결과를 기록하는 함수
+    pub fn insert(
+        &mut self,
+        hit: &FileHit,
+        hash: String,
+        chunk_ids: Vec<String>,
+        loader_version: u32,
+    ) {
+        self.entries.insert(
+            hit.path.clone(),
+            ManifestEntry {
+                size: hit.size,
+                modified: hit.modified,
+                hash,
+                chunk_ids,
+                loader_version,
+            },
+        );
+        self.dirty = true;
+    }
+
+    /// `path` 파일 (디렉토리인 경우 하위 파일 전체)의 기록을 삭제하고, 삭제된 파일 경로를 반환하는 함수
+    pub fn remove(&mut self, path: &Path) -> Vec<PathBuf> {
+        let removed = self
+            .entries
+            .keys()
+            .filter(|entry_path| entry_path.starts_with(path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for entry_path in &removed {
+            self.entries.remove(entry_path);
+        }
+        self.dirty |= !removed.is_empty();
+
+        removed
+    }
+
+    /// 기록되어 있지만 더 이상 존재하지 않는 파일 경로를 반환하는 함수
+    pub fn missing(&self) -> Vec<PathBuf> {
+        self.entries
+            .keys()
+            .filter(|path| !path.exists())
+            .cloned()
+            .collect()
+    }
+}
+
+fn check_entry(
+    entry: Option<&ManifestEntry>,
+    hit: &FileHit,
+    loader_version: u32,
+) -> std::io::Result<FileState> {
+    if let Some(entry) = entry {
+        if entry.loader_version == loader_version
+            && entry.size == hit.size
+            && entry.modified == hit.modified
+        {
+            return Ok(FileState::Unchanged);
+        }
+    }
+
+    let hash = hash_file(&hit.path)?;
+    let state = match entry {
+        None => FileState::New { hash },
+        Some(entry) if entry.loader_version != loader_version || entry.hash != hash => {
+            FileState::Modified { hash }
+        }
+        Some(_) => FileState::Touched,
+    };
+
+    Ok(state)
+}
+
+/// 파일 내용의 SHA-256을 16진수 문자열로 계산하는 함수
+fn hash_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let length = file.read(&mut buffer)?;
+        if length == 0 {
+            break;
+        }
+        hasher.update(&buffer[..length]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
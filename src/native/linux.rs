@@ -0,0 +1,304 @@
+use std::{
+    fs::ReadDir,
+    io::{BufRead, BufReader, ErrorKind},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdout, Command, Stdio},
+};
+
+use super::{
+    CancelToken, FileHit, HitSource, QueryMode, SearchErrorKind, SearchFilter, SearchOptions,
+};
+
+pub type SearchLocalFileResult<T> = Result<T, SearchLocalFileError>;
+
+#[derive(Debug)]
+pub enum SearchLocalFileError {
+    HomeDirNotFound,
+    /// `locate`와 파일 시스템 탐색은 문서 내용을 검색할 수 없음
+    ContentSearchUnsupported,
+    /// (Error, Path)
+    ReadDir(std::io::Error, PathBuf),
+}
+
+impl SearchLocalFileError {
+    /// 플랫폼과 관계없는 오류 종류를 반환하는 함수
+    pub fn kind(&self) -> SearchErrorKind {
+        match self {
+            Self::HomeDirNotFound => SearchErrorKind::Other,
+            Self::ContentSearchUnsupported => SearchErrorKind::IndexUnavailable,
+            Self::ReadDir(e, _) if e.kind() == ErrorKind::PermissionDenied => {
+                SearchErrorKind::PermissionDenied
+            }
+            Self::ReadDir(..) => SearchErrorKind::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for SearchLocalFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.kind())?;
+        match self {
+            Self::HomeDirNotFound => write!(f, "`HOME` is not set and no path prefix is given"),
+            Self::ContentSearchUnsupported => write!(f, "content search is not supported on Linux"),
+            Self::ReadDir(_, path) => write!(f, "failed to read {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for SearchLocalFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::HomeDirNotFound | Self::ContentSearchUnsupported => None,
+            Self::ReadDir(e, _) => Some(e),
+        }
+    }
+}
+
+/// `query` 문자열로 파일 시스템을 검색하는 함수
+///
+/// `locate` 데이터베이스를 사용할 수 있으면 먼저 조회하고,
+/// 사용할 수 없으면 `filter.path_prefixes` (없으면 홈 디렉토리)부터 재귀적으로 탐색한다.
+/// 두 방식 모두 결과를 한 번에 모으지 않고 `SearchHits`에서 필요할 때마다 가져온다.
+/// 문서 내용을 색인하지 않으므로 `QueryMode::Content`는 지원하지 않는다.
+pub fn search_local_files(
+    query: String,
+    filter: &SearchFilter,
+    options: &SearchOptions,
+) -> SearchLocalFileResult<SearchHits> {
+    if options.mode == QueryMode::Content {
+        return Err(SearchLocalFileError::ContentSearchUnsupported);
+    }
+    let query = query.to_lowercase();
+    let source = match spawn_locate(&query) {
+        Some(locate) => HitsSource::Locate(locate),
+        None => HitsSource::Walk(Walk::new(walk_roots(filter)?)),
+    };
+
+    Ok(SearchHits {
+        query,
+        source,
+        filter: filter.clone(),
+        remaining: options.limit,
+        cancel: options.cancel.clone(),
+    })
+}
+
+/// 파일 시스템 검색 결과를 하나씩 가져오는 반복자
+pub struct SearchHits {
+    query: String,
+    source: HitsSource,
+    filter: SearchFilter,
+    /// 남은 최대 결과 수 (`None`이면 제한 없음)
+    remaining: Option<usize>,
+    cancel: CancelToken,
+}
+
+enum HitsSource {
+    Locate(Locate),
+    Walk(Walk),
+}
+
+impl Iterator for SearchHits {
+    type Item = SearchLocalFileResult<FileHit>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) || self.cancel.is_cancelled() {
+            return None;
+        }
+        loop {
+            let (path, source) = match &mut self.source {
+                HitsSource::Locate(locate) => match locate.next_path() {
+                    LocatePath::Path(path) => (path, HitSource::Locate),
+                    LocatePath::End => return None,
+                    // `locate` 데이터베이스가 없는 경우 파일 시스템 탐색으로 전환
+                    LocatePath::Failed => match walk_roots(&self.filter) {
+                        Ok(roots) => {
+                            self.source = HitsSource::Walk(Walk::new(roots));
+                            continue;
+                        }
+                        Err(e) => return Some(Err(e)),
+                    },
+                },
+                HitsSource::Walk(walk) => match walk.next_path(&self.query)? {
+                    Ok(path) => (path, HitSource::FileSystem),
+                    Err(e) => return Some(Err(e)),
+                },
+            };
+
+            if let Some(hit) = create_file_hit(path, &self.filter, source) {
+                if let Some(remaining) = self.remaining.as_mut() {
+                    *remaining -= 1;
+                }
+                return Some(Ok(hit));
+            }
+        }
+    }
+}
+
+/// 실행 중인 `locate` 프로세스의 출력을 읽는 구조체
+struct Locate {
+    child: Child,
+    reader: BufReader<ChildStdout>,
+    /// 지금까지 읽은 경로의 수
+    count: usize,
+}
+
+enum LocatePath {
+    Path(PathBuf),
+    End,
+    /// `locate`가 아무 결과 없이 실패한 경우
+    Failed,
+}
+
+/// `locate` 명령어로 파일 이름 검색을 시작하는 함수
+///
+/// `locate`가 설치되어 있지 않으면 `None`을 반환한다.
+fn spawn_locate(query: &str) -> Option<Locate> {
+    let mut child = Command::new("locate")
+        .args(["--basename", "--ignore-case", "--null", query])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let stdout = child.stdout.take()?;
+
+    Some(Locate {
+        child,
+        reader: BufReader::new(stdout),
+        count: 0,
+    })
+}
+
+impl Locate {
+    fn next_path(&mut self) -> LocatePath {
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            match self.reader.read_until(0, &mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            if buffer.last() == Some(&0) {
+                buffer.pop();
+            }
+            if buffer.is_empty() {
+                continue;
+            }
+
+            self.count += 1;
+            return LocatePath::Path(PathBuf::from(String::from_utf8_lossy(&buffer).into_owned()));
+        }
+
+        // 검색 결과가 없을 때도 실패 코드가 반환되므로, 결과가 하나도 없을 때만 실패로 판단
+        let success = self.child.wait().is_ok_and(|status| status.success());
+        if !success && self.count == 0 {
+            LocatePath::Failed
+        } else {
+            LocatePath::End
+        }
+    }
+}
+
+impl Drop for Locate {
+    fn drop(&mut self) {
+        // 모든 결과를 읽기 전에 반복을 멈춘 경우 프로세스를 종료
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// 디렉토리를 재귀적으로 탐색하는 구조체
+struct Walk {
+    /// 아직 탐색하지 않은 루트 디렉토리
+    roots: Vec<PathBuf>,
+    /// 탐색 중인 디렉토리의 스택
+    stack: Vec<ReadDir>,
+}
+
+impl Walk {
+    fn new(mut roots: Vec<PathBuf>) -> Self {
+        // `pop`으로 꺼내므로 순서를 뒤집어 둠
+        roots.reverse();
+        Self {
+            roots,
+            stack: Vec::new(),
+        }
+    }
+
+    /// `query`에 해당하는 다음 파일 경로를 찾는 함수
+    fn next_path(&mut self, query: &str) -> Option<SearchLocalFileResult<PathBuf>> {
+        loop {
+            let Some(entries) = self.stack.last_mut() else {
+                let root = self.roots.pop()?;
+                match std::fs::read_dir(&root) {
+                    Ok(entries) => self.stack.push(entries),
+                    Err(e) => return Some(Err(SearchLocalFileError::ReadDir(e, root))),
+                }
+                continue;
+            };
+            let Some(entry) = entries.next() else {
+                self.stack.pop();
+                continue;
+            };
+            let Ok(entry) = entry else {
+                continue;
+            };
+            // 숨김 파일 및 디렉토리는 제외
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            let path = entry.path();
+            if file_type.is_dir() {
+                // 권한이 없는 하위 디렉토리는 무시
+                if let Ok(entries) = std::fs::read_dir(&path) {
+                    self.stack.push(entries);
+                }
+            } else if file_type.is_file() && matches_query(&path, query) {
+                return Some(Ok(path));
+            }
+        }
+    }
+}
+
+/// 파일 이름이 `query`를 포함하는지 확인하는 함수
+fn matches_query(path: &Path, query: &str) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase().contains(query))
+        .unwrap_or(false)
+}
+
+/// `filter` 조건을 확인하고 `FileHit`을 생성하는 함수
+fn create_file_hit(path: PathBuf, filter: &SearchFilter, source: HitSource) -> Option<FileHit> {
+    // 메타데이터를 읽기 전에 확장자로 먼저 거름
+    if !filter.matches_extension(&path) {
+        return None;
+    }
+
+    let metadata = std::fs::metadata(&path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+
+    let hit = FileHit::from_metadata(path, &metadata, source);
+    filter.matches(&hit).then_some(hit)
+}
+
+/// 파일 시스템 탐색을 시작할 디렉토리 목록을 반환하는 함수
+fn walk_roots(filter: &SearchFilter) -> SearchLocalFileResult<Vec<PathBuf>> {
+    if filter.path_prefixes.is_empty() {
+        Ok(vec![home_dir()?])
+    } else {
+        Ok(filter.path_prefixes.clone())
+    }
+}
+
+fn home_dir() -> SearchLocalFileResult<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .ok_or(SearchLocalFileError::HomeDirNotFound)
+}
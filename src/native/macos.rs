@@ -1,163 +1,455 @@
-use core_foundation_sys::runloop::{CFRunLoopGetMain, CFRunLoopRun, CFRunLoopStop};
+use std::{
+    ffi::NulError,
+    io::ErrorKind,
+    path::PathBuf,
+    sync::OnceLock,
+    time::{Duration, Instant, SystemTime},
+};
+
+use core_foundation_sys::runloop::{kCFRunLoopDefaultMode, CFRunLoopRunInMode};
 use objc::{
     class,
     declare::ClassDecl,
     msg_send,
-    runtime::{Class, Object, Sel},
+    runtime::{Class, Object, Sel, BOOL, NO, YES},
     sel, sel_impl,
 };
 
-pub type SearchData = (String, String, String);
+use super::{
+    content_terms, file_name, guess_mime, to_utc_datetime, CancelToken, FileHit, FileProperties,
+    HitSource, QueryMode, SearchErrorKind, SearchFilter, SearchOptions,
+};
+
 pub type SearchLocalFileResult<T> = Result<T, SearchLocalFileError>;
 
 #[derive(Debug)]
 pub enum SearchLocalFileError {
-    #[allow(unused)]
-    CreateCString(String),
+    CreateCString(NulError),
+    /// Spotlight가 쿼리 문자열을 해석하지 못한 경우
+    InvalidPredicate(String),
+    /// 검색 범위(`SearchFilter::path_prefixes`)를 읽을 수 없는 경우 (Error, Path)
+    ReadScope(std::io::Error, PathBuf),
+    /// Spotlight가 꺼져 있는 등의 이유로 쿼리를 시작하지 못한 경우
+    StartQuery,
+    /// `SearchOptions::timeout` 안에 검색이 끝나지 않은 경우
+    Timeout(Duration),
+    /// `SearchOptions::cancel`로 검색이 취소된 경우
+    Cancelled,
+}
+
+impl SearchLocalFileError {
+    /// 플랫폼과 관계없는 오류 종류를 반환하는 함수
+    pub fn kind(&self) -> SearchErrorKind {
+        match self {
+            Self::CreateCString(_) | Self::InvalidPredicate(_) => SearchErrorKind::QuerySyntax,
+            Self::ReadScope(e, _) if e.kind() == ErrorKind::PermissionDenied => {
+                SearchErrorKind::PermissionDenied
+            }
+            Self::ReadScope(..) => SearchErrorKind::Other,
+            Self::StartQuery => SearchErrorKind::IndexUnavailable,
+            Self::Timeout(_) => SearchErrorKind::Timeout,
+            Self::Cancelled => SearchErrorKind::Cancelled,
+        }
+    }
+}
+
+impl std::fmt::Display for SearchLocalFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.kind())?;
+        match self {
+            Self::CreateCString(_) => write!(f, "query contains a null character"),
+            Self::InvalidPredicate(predicate) => {
+                write!(f, "Spotlight could not parse the query `{predicate}`")
+            }
+            Self::ReadScope(_, path) => write!(f, "failed to read {}", path.display()),
+            Self::StartQuery => write!(f, "Spotlight query could not be started"),
+            Self::Timeout(timeout) => write!(f, "Spotlight did not finish in {timeout:?}"),
+            Self::Cancelled => write!(f, "Spotlight query was cancelled"),
+        }
+    }
 }
 
-/// 요청했던 `NSMetadataQuery` 객체를 저장하는 전역 변수
-static mut QUERY: Option<*mut Object> = None;
+impl std::error::Error for SearchLocalFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::CreateCString(e) => Some(e),
+            Self::ReadScope(e, _) => Some(e),
+            Self::InvalidPredicate(_) | Self::StartQuery | Self::Timeout(_) | Self::Cancelled => {
+                None
+            }
+        }
+    }
+}
+
+/// `RunLoop`를 한 번 실행할 때 최대로 기다리는 시간 (초)
+///
+/// 이 주기마다 시간 초과와 취소 여부를 확인한다.
+const RUN_LOOP_INTERVAL: f64 = 0.1;
+
+/// 범위를 벗어날 때 해제되는 `NSAutoreleasePool`
+///
+/// `tokio`의 블로킹 스레드 등 Cocoa가 만들지 않은 스레드에는 기본 풀이 없으므로, `autorelease`된
+/// 객체(`NSString`, 결과 배열의 속성 값 등)가 해제되지 않고 쌓이지 않도록 호출마다 풀을 만든다.
+struct AutoreleasePool(*mut Object);
+
+impl AutoreleasePool {
+    fn new() -> Self {
+        Self(unsafe { msg_send![class!(NSAutoreleasePool), new] })
+    }
+}
+
+impl Drop for AutoreleasePool {
+    fn drop(&mut self) {
+        let _: () = unsafe { msg_send![self.0, drain] };
+    }
+}
 
 /// `query` 문자열로 파일 시스템의 인덱스를 검색하는 함수
-pub fn search_local_files_by_query(
+///
+/// Spotlight는 검색이 끝난 후 결과 배열을 제공하므로, `SearchHits`는 배열의 원소를
+/// 필요할 때마다 하나씩 `FileHit`으로 변환한다.
+/// 호출마다 별도의 쿼리와 알림 수신 객체를 만들고 현재 스레드의 `RunLoop`에서 완료를 기다리므로,
+/// 여러 스레드에서 동시에 호출할 수 있다.
+pub fn search_local_files(
     query_file_name: String,
-) -> SearchLocalFileResult<Vec<SearchData>> {
-    let query = unsafe { create_search_query(query_file_name)? };
-    let query = unsafe { connect_notification_center(query) };
-    let query = unsafe { run_query(query) };
-    unsafe { process_query_data(query, Some(20)) }
+    filter: &SearchFilter,
+    options: &SearchOptions,
+) -> SearchLocalFileResult<SearchHits> {
+    let _pool = AutoreleasePool::new();
+    let query = unsafe { create_search_query(query_file_name, filter, options.mode)? };
+    let observer = unsafe { connect_notification_center(query) };
+    let result = unsafe { run_query(query, observer, options) };
+    unsafe { disconnect_notification_center(query, observer) };
+    if let Err(e) = result {
+        let _: () = unsafe { msg_send![query, release] };
+        return Err(e);
+    }
+
+    // 결과를 읽는 동안 결과 배열이 갱신되지 않도록 함
+    let _: () = unsafe { msg_send![query, disableUpdates] };
+    // 결과 배열은 `autorelease`된 객체이므로 풀이 해제된 후에도 쓸 수 있도록 유지
+    let results: *mut Object = unsafe { msg_send![query, results] };
+    let _: () = unsafe { msg_send![results, retain] };
+    let count: usize = unsafe { msg_send![results, count] };
+
+    Ok(SearchHits {
+        query,
+        results,
+        index: 0,
+        count,
+        remaining: options.limit,
+        filter: filter.clone(),
+        cancel: options.cancel.clone(),
+    })
+}
+
+/// Spotlight 쿼리 결과를 하나씩 가져오는 반복자
+pub struct SearchHits {
+    query: *mut Object,
+    results: *mut Object,
+    index: usize,
+    count: usize,
+    /// 남은 최대 결과 수 (`None`이면 제한 없음)
+    remaining: Option<usize>,
+    filter: SearchFilter,
+    cancel: CancelToken,
+}
+
+impl Iterator for SearchHits {
+    type Item = SearchLocalFileResult<FileHit>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        while self.index < self.count {
+            // 결과가 많으면 변환에도 시간이 걸리므로 항목마다 취소 여부를 확인
+            if self.cancel.is_cancelled() {
+                self.index = self.count;
+                return Some(Err(SearchLocalFileError::Cancelled));
+            }
+            let result: *mut Object = unsafe { msg_send![self.results, objectAtIndex: self.index] };
+            self.index += 1;
+
+            let _pool = AutoreleasePool::new();
+            let hit = unsafe { create_file_hit(result) };
+            // Spotlight의 와일드카드 비교 결과와 파일 시스템의 값이 다를 수 있으므로 한 번 더 확인
+            if self.filter.matches(&hit) {
+                if let Some(remaining) = self.remaining.as_mut() {
+                    *remaining -= 1;
+                }
+                return Some(Ok(hit));
+            }
+        }
+
+        None
+    }
+}
+
+impl Drop for SearchHits {
+    fn drop(&mut self) {
+        unsafe {
+            let _: () = msg_send![self.results, release];
+            let _: () = msg_send![self.query, enableUpdates];
+            let _: () = msg_send![self.query, release];
+        }
+    }
 }
 
 /// Spotlight 쿼리를 생성하는 함수
-unsafe fn create_search_query(query_file_name: String) -> SearchLocalFileResult<*mut Object> {
+unsafe fn create_search_query(
+    query_file_name: String,
+    filter: &SearchFilter,
+    mode: QueryMode,
+) -> SearchLocalFileResult<*mut Object> {
     // `NSMetadataQuery` 객체 생성
     let query: *mut Object = msg_send![class!(NSMetadataQuery), alloc];
     let query: *mut Object = msg_send![query, init];
 
-    let predicate_format: *mut Object = {
-        // https://developer.apple.com/library/archive/documentation/Carbon/Conceptual/SpotlightQuery/Concepts/QueryFormat.html#//apple_ref/doc/uid/TP40001849
-        let c_string =
-            std::ffi::CString::new(format!("(kMDItemFSName == '*.pdf' || kMDItemFSName == '*.png' || kMDItemFSName == '*.jpeg') && kMDItemDisplayName == \"*{}*\"cd", query_file_name))
-                .map_err(|e| SearchLocalFileError::CreateCString(e.to_string()))?;
-        msg_send![class!(NSString), stringWithUTF8String: c_string.as_ptr()]
-    };
+    let predicate_string = create_predicate_format(&query_file_name, filter, mode);
+    let predicate_format = create_ns_string(&predicate_string)?;
     let predicate: *mut Object =
         msg_send![class!(NSPredicate), predicateFromMetadataQueryString: predicate_format];
+    if predicate.is_null() {
+        let _: () = msg_send![query, release];
+        return Err(SearchLocalFileError::InvalidPredicate(predicate_string));
+    }
     // `NSMetadataQuery`에 predicate 설정
     let _: () = msg_send![query, setPredicate: predicate];
 
+    // 경로 조건은 predicate가 아닌 검색 범위로 설정
+    if !filter.path_prefixes.is_empty() {
+        let scopes: *mut Object = msg_send![class!(NSMutableArray), array];
+        for prefix in &filter.path_prefixes {
+            // 읽을 수 없는 범위는 Spotlight가 오류 없이 빈 결과를 반환하므로 미리 확인
+            if let Err(e) = std::fs::read_dir(prefix) {
+                let _: () = msg_send![query, release];
+                return Err(SearchLocalFileError::ReadScope(e, prefix.clone()));
+            }
+            let scope = create_ns_string(&prefix.to_string_lossy())?;
+            let _: () = msg_send![scopes, addObject: scope];
+        }
+        let _: () = msg_send![query, setSearchScopes: scopes];
+    }
+
+    // 문서 내용 검색은 관련도가 높은 결과부터 가져옴
+    if mode == QueryMode::Content {
+        let key = create_ns_string("kMDQueryResultContentRelevance")?;
+        let descriptor: *mut Object =
+            msg_send![class!(NSSortDescriptor), sortDescriptorWithKey: key ascending: NO];
+        let descriptors: *mut Object = msg_send![class!(NSArray), arrayWithObject: descriptor];
+        let _: () = msg_send![query, setSortDescriptors: descriptors];
+    }
+
     Ok(query)
 }
 
-/// `NSMetadataQuery` 객체에 `NSNotificationCenter`를 연결하는 함수
-unsafe fn connect_notification_center(query: *mut Object) -> *mut Object {
-    let observer_class = Class::get("QueryDelegate").unwrap_or_else(|| {
-        let mut decl = ClassDecl::new("QueryDelegate", class!(NSObject)).unwrap();
-        decl.add_method(
-            sel!(queryDidFinishGathering:),
-            query_did_finish_gathering as extern "C" fn(&Object, Sel, *mut Object),
-        );
+/// `SearchFilter`를 Spotlight 쿼리 문자열로 변환하는 함수
+///
+/// https://developer.apple.com/library/archive/documentation/Carbon/Conceptual/SpotlightQuery/Concepts/QueryFormat.html#//apple_ref/doc/uid/TP40001849
+fn create_predicate_format(
+    query_file_name: &str,
+    filter: &SearchFilter,
+    mode: QueryMode,
+) -> String {
+    let mut conditions = Vec::new();
+    if !filter.extensions.is_empty() {
+        let extensions = filter
+            .extensions
+            .iter()
+            .map(|extension| format!("kMDItemFSName == \"*.{}\"c", escape_query(extension)))
+            .collect::<Vec<_>>();
+        conditions.push(format!("({})", extensions.join(" || ")));
+    }
+    if let Some(min_size) = filter.min_size {
+        conditions.push(format!("kMDItemFSSize >= {min_size}"));
+    }
+    if let Some(max_size) = filter.max_size {
+        conditions.push(format!("kMDItemFSSize < {max_size}"));
+    }
+    if let Some(after) = filter.modified_after {
+        conditions.push(format!(
+            "kMDItemFSContentChangeDate >= {}",
+            format_query_datetime(after)
+        ));
+    }
+    if let Some(before) = filter.modified_before {
+        conditions.push(format!(
+            "kMDItemFSContentChangeDate < {}",
+            format_query_datetime(before)
+        ));
+    }
+    let terms = content_terms(query_file_name);
+    if mode == QueryMode::Content && !terms.is_empty() {
+        // 질문의 단어 중 하나라도 문서 내용에 포함된 파일 (`w`: 단어 단위 비교)
+        let terms = terms
+            .iter()
+            .map(|term| format!("kMDItemTextContent == \"{}*\"cdw", escape_query(term)))
+            .collect::<Vec<_>>();
+        conditions.push(format!("({})", terms.join(" || ")));
+    } else {
+        conditions.push(format!(
+            "kMDItemDisplayName == \"*{}*\"cd",
+            escape_query(query_file_name)
+        ));
+    }
+
+    conditions.join(" && ")
+}
+
+/// Spotlight 쿼리의 날짜 형식(`$time.iso(...)`, UTC)으로 변환하는 함수
+fn format_query_datetime(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = to_utc_datetime(time);
+    format!("$time.iso({year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z)")
+}
+
+fn escape_query(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// `String`을 `NSString` 객체로 변환하는 함수
+unsafe fn create_ns_string(string: &str) -> SearchLocalFileResult<*mut Object> {
+    let c_string = std::ffi::CString::new(string).map_err(SearchLocalFileError::CreateCString)?;
+    Ok(msg_send![class!(NSString), stringWithUTF8String: c_string.as_ptr()])
+}
+
+/// 검색 완료 알림을 받는 `QueryDelegate` 클래스를 반환하는 함수
+///
+/// 여러 스레드에서 동시에 등록하지 않도록 처음 호출될 때 한 번만 등록한다.
+fn query_delegate_class() -> &'static Class {
+    static QUERY_DELEGATE: OnceLock<&'static Class> = OnceLock::new();
+    QUERY_DELEGATE.get_or_init(|| {
+        let mut decl = ClassDecl::new("QueryDelegate", class!(NSObject))
+            .expect("QueryDelegate class is already registered");
+        // 검색 완료 여부를 인스턴스마다 저장
+        decl.add_ivar::<BOOL>("finished");
+        unsafe {
+            decl.add_method(
+                sel!(queryDidFinishGathering:),
+                query_did_finish_gathering as extern "C" fn(&mut Object, Sel, *mut Object),
+            );
+        }
         decl.register()
-    });
+    })
+}
+
+/// `NSMetadataQuery` 객체의 완료 알림을 받을 객체를 생성하여 `NSNotificationCenter`에 연결하는 함수
+unsafe fn connect_notification_center(query: *mut Object) -> *mut Object {
+    let observer: *mut Object = msg_send![query_delegate_class(), new];
+    (*observer).set_ivar::<BOOL>("finished", NO);
 
-    let observer_instance: *mut Object = msg_send![observer_class, new];
     let notification_center: *mut Object = msg_send![class!(NSNotificationCenter), defaultCenter];
-    let _: () = msg_send![notification_center, addObserver: observer_instance
+    let _: () = msg_send![notification_center, addObserver: observer
         selector: sel!(queryDidFinishGathering:)
         name: NSMetadataQueryDidFinishGatheringNotification
         object: query
     ];
 
-    query
+    observer
 }
 
-/// `NSMetadataQuery` 객체를 실행하는 함수
-unsafe fn run_query(query: *mut Object) -> *mut Object {
-    let previous_query = QUERY.replace(query);
-    if let Some(previous_query) = previous_query {
-        stop_query(previous_query);
-    }
-    let _ = QUERY.insert(query);
-    // `NSMetadataQuery` 시작
-    let _: () = msg_send![query, startQuery];
-    CFRunLoopRun();
-    query
+/// `connect_notification_center`로 연결한 객체의 연결을 끊고 해제하는 함수
+unsafe fn disconnect_notification_center(query: *mut Object, observer: *mut Object) {
+    let notification_center: *mut Object = msg_send![class!(NSNotificationCenter), defaultCenter];
+    let _: () = msg_send![notification_center, removeObserver: observer
+        name: NSMetadataQueryDidFinishGatheringNotification
+        object: query
+    ];
+    let _: () = msg_send![observer, release];
 }
 
-/// `NSMetadataQuery` 객체의 결과를 처리하는 함수
-unsafe fn process_query_data(
+/// `NSMetadataQuery` 객체를 실행하고 검색이 끝날 때까지 기다리는 함수
+///
+/// 완료 알림은 쿼리를 시작한 스레드의 `RunLoop`로 전달되므로, 현재 스레드의 `RunLoop`를
+/// 짧게 반복 실행하면서 완료, 시간 초과, 취소 여부를 확인한다.
+unsafe fn run_query(
     query: *mut Object,
-    max_count: Option<usize>,
-) -> SearchLocalFileResult<Vec<SearchData>> {
-    // 검색 완료된 결과 리스트를 가져옴
-    let results: *mut Object = msg_send![query, results];
-    // 결과 수 가져오기
-    let result_count: usize = msg_send![results, count];
-    let result_count = max_count.map_or(result_count, |max| max.min(result_count)); // 지정한 최대 갯수만큼 결과를 가져옴
+    observer: *mut Object,
+    options: &SearchOptions,
+) -> SearchLocalFileResult<()> {
+    let started_at = Instant::now();
+    // `NSMetadataQuery` 시작
+    let started: BOOL = msg_send![query, startQuery];
+    if started == NO {
+        return Err(SearchLocalFileError::StartQuery);
+    }
 
-    // 결과를 Vec<String>으로 저장
-    let mut result_vec = Vec::new();
-    for i in 0..result_count {
-        let result: *mut Object = msg_send![results, objectAtIndex: i];
-        let file_name: *mut Object = msg_send![result, valueForAttribute: NSMetadataItemFSNameKey];
-        let path: *mut Object = msg_send![result, valueForAttribute: NSMetadataItemPathKey];
-        let modified_date: *mut Object =
-            msg_send![result, valueForAttribute: NSMetadataItemAttributeChangeDateKey];
+    let result = loop {
+        let finished: BOOL = *(*observer).get_ivar("finished");
+        if finished != NO {
+            break Ok(());
+        }
+        if options.cancel.is_cancelled() {
+            break Err(SearchLocalFileError::Cancelled);
+        }
+        if let Some(timeout) = options.timeout {
+            if started_at.elapsed() >= timeout {
+                break Err(SearchLocalFileError::Timeout(timeout));
+            }
+        }
+        CFRunLoopRunInMode(kCFRunLoopDefaultMode, RUN_LOOP_INTERVAL, 1);
+    };
 
-        let file_name = nsstring_to_string(file_name);
-        let path = nsstring_to_string(path);
-        let modified_date = nsdate_to_string(modified_date)?;
-        result_vec.push((file_name, path, modified_date));
-    }
+    // 검색이 끝난 후에는 결과가 갱신되지 않도록 중단
+    stop_query(query);
+    result
+}
+
+/// `NSMetadataItem` 객체를 `FileHit`으로 변환하는 함수
+unsafe fn create_file_hit(result: *mut Object) -> FileHit {
+    let path: *mut Object = msg_send![result, valueForAttribute: NSMetadataItemPathKey];
+    let size: *mut Object = msg_send![result, valueForAttribute: NSMetadataItemFSSizeKey];
+    let modified_date: *mut Object =
+        msg_send![result, valueForAttribute: NSMetadataItemFSContentChangeDateKey];
 
-    Ok(result_vec)
+    let path = PathBuf::from(nsstring_to_string(path));
+    // `nil`에 메시지를 보내면 0이 반환됨
+    let size: u64 = msg_send![size, unsignedLongLongValue];
+    FileHit {
+        name: file_name(&path),
+        mime: guess_mime(&path),
+        size,
+        modified: nsdate_to_system_time(modified_date),
+        path,
+        source: HitSource::Spotlight,
+        properties: FileProperties::default(),
+    }
 }
 
 /// `NSString` 객체를 `String`으로 변환하는 함수
 unsafe fn nsstring_to_string(string: *mut Object) -> String {
     let utf8_string: *const i8 = msg_send![string, UTF8String];
+    if utf8_string.is_null() {
+        return String::new();
+    }
     let c_str = std::ffi::CStr::from_ptr(utf8_string);
     let rust_str = c_str.to_string_lossy().into_owned();
     rust_str
 }
 
-/// `NSDate` 객체를 `String`으로 변환하는 함수
-unsafe fn nsdate_to_string(date: *mut Object) -> SearchLocalFileResult<String> {
-    let date_formatter: *mut Object = msg_send![class!(NSDateFormatter), new];
-    let format_ns_string: *mut Object = {
-        let c_string = std::ffi::CString::new("yyyy-MM-dd HH:mm:ss")
-            .map_err(|e| SearchLocalFileError::CreateCString(e.to_string()))?;
-        msg_send![class!(NSString), stringWithUTF8String: c_string.as_ptr()]
-    };
-    let _: () = msg_send![date_formatter, setDateFormat: format_ns_string];
-    let ns_string: *mut Object = msg_send![date_formatter, stringFromDate: date];
-    let utf8_string: *const i8 = msg_send![ns_string, UTF8String];
-    let c_str = std::ffi::CStr::from_ptr(utf8_string);
-    let rust_str = c_str.to_string_lossy().into_owned();
-    let _: () = msg_send![date_formatter, release];
-    Ok(rust_str)
+/// `NSDate` 객체를 `SystemTime`으로 변환하는 함수
+unsafe fn nsdate_to_system_time(date: *mut Object) -> SystemTime {
+    if date.is_null() {
+        return SystemTime::UNIX_EPOCH;
+    }
+    let seconds: f64 = msg_send![date, timeIntervalSince1970];
+    SystemTime::UNIX_EPOCH + Duration::from_secs_f64(seconds.max(0.0))
 }
 
 unsafe fn stop_query(query: *mut Object) {
     let _: () = msg_send![query, stopQuery];
-    CFRunLoopStop(CFRunLoopGetMain());
 }
 
 extern "C" {
     // `NSMetadataQueryDidFinishGatheringNotification`은 NSString* 타입으로 정의됨
     static NSMetadataQueryDidFinishGatheringNotification: *const Object;
 
-    static NSMetadataItemFSNameKey: *const Object;
     static NSMetadataItemPathKey: *const Object;
-    static NSMetadataItemAttributeChangeDateKey: *const Object;
+    static NSMetadataItemFSSizeKey: *const Object;
+    static NSMetadataItemFSContentChangeDateKey: *const Object;
 }
 
-extern "C" fn query_did_finish_gathering(_this: &Object, _: Sel, _notification: *mut Object) {
-    // 알림 발생 후 RunLoop 종료
-    unsafe {
-        if let Some(query) = QUERY.take() {
-            stop_query(query);
-        }
-    }
+extern "C" fn query_did_finish_gathering(this: &mut Object, _: Sel, _notification: *mut Object) {
+    // 완료 여부만 저장하고, 쿼리 중단은 `run_query`에서 처리
+    unsafe { this.set_ivar::<BOOL>("finished", YES) };
 }
@@ -1,3 +1,16 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::SystemTime,
+};
+
+#[cfg(target_os = "macos")]
+use std::time::Duration;
+
 #[cfg(target_os = "windows")]
 mod windows;
 #[cfg(target_os = "windows")]
@@ -7,3 +20,422 @@ pub use windows::*;
 mod macos;
 #[cfg(target_os = "macos")]
 pub use macos::*;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+pub use linux::*;
+
+/// `query` 문자열로 파일 시스템의 인덱스를 검색하여 모든 결과를 가져오는 함수
+///
+/// 결과가 많을 수 있는 경우에는 `search_local_files`로 페이지 단위로 가져오는 것이 좋다.
+pub fn search_local_files_by_query(
+    query: String,
+    filter: &SearchFilter,
+    options: &SearchOptions,
+) -> SearchLocalFileResult<Vec<FileHit>> {
+    search_local_files(query, filter, options)?.collect()
+}
+
+/// `search_local_files_by_query`를 `tokio`의 블로킹 스레드에서 실행하여 모든 결과를 가져오는 비동기 함수
+///
+/// OS 검색 API는 호출한 스레드를 점유하므로, 런타임의 작업 스레드를 막지 않도록 별도 스레드에서 실행한다.
+/// 검색을 중단하려면 `options.cancel`을 취소한다.
+pub async fn search_local_files_async(
+    query: String,
+    filter: SearchFilter,
+    options: SearchOptions,
+) -> SearchLocalFileResult<Vec<FileHit>> {
+    tokio::task::spawn_blocking(move || search_local_files_by_query(query, &filter, &options))
+        .await
+        .expect("Failed to join search task")
+}
+
+/// 플랫폼과 관계없이 검색 오류를 분류한 종류
+///
+/// 호출하는 쪽이 플랫폼마다 다르게 분기하지 않도록 모든 플랫폼에서 같은 종류를 정의한다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum SearchErrorKind {
+    /// 파일, 디렉토리 또는 검색 서비스에 접근할 권한이 없음
+    PermissionDenied,
+    /// OS 인덱스(Windows Search, Spotlight, `locate`)를 사용할 수 없음
+    IndexUnavailable,
+    /// 검색어나 `SearchFilter`로 쿼리를 만들 수 없음
+    QuerySyntax,
+    /// `SearchOptions::timeout` 안에 검색이 끝나지 않음
+    Timeout,
+    /// `SearchOptions::cancel`로 검색이 취소됨
+    Cancelled,
+    Other,
+}
+
+impl std::fmt::Display for SearchErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            Self::PermissionDenied => "permission denied",
+            Self::IndexUnavailable => "index unavailable",
+            Self::QuerySyntax => "invalid query",
+            Self::Timeout => "timed out",
+            Self::Cancelled => "cancelled",
+            Self::Other => "search failed",
+        };
+        f.write_str(kind)
+    }
+}
+
+/// 검색 결과를 가져오는 방식
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    /// OS 인덱스로부터 한 번에 가져올 결과 수
+    pub page_size: usize,
+    /// 최대 결과 수 (`None`이면 제한 없음)
+    pub limit: Option<usize>,
+    /// 검색어를 해석하는 방식
+    pub mode: QueryMode,
+    /// 검색 결과가 모두 모일 때까지 기다리는 최대 시간 (`None`이면 제한 없음)
+    ///
+    /// 결과를 한 번에 제공하는 Spotlight 검색에서만 사용한다.
+    #[cfg(target_os = "macos")]
+    pub timeout: Option<Duration>,
+    /// 다른 스레드에서 검색을 중단하기 위한 토큰
+    pub cancel: CancelToken,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            page_size: 100,
+            limit: None,
+            mode: QueryMode::default(),
+            #[cfg(target_os = "macos")]
+            timeout: Some(Duration::from_secs(30)),
+            cancel: CancelToken::default(),
+        }
+    }
+}
+
+/// 검색어를 해석하는 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueryMode {
+    /// 파일 이름에 검색어가 포함된 파일을 검색
+    #[default]
+    FileName,
+    /// OS 인덱스에 저장된 문서 내용으로 검색어와 관련된 파일을 검색
+    ///
+    /// 질문 문장을 그대로 사용하여 임베딩할 후보 파일을 미리 줄이기 위해 사용하며,
+    /// 결과는 OS 인덱스가 계산한 관련도 순서로 반환된다.
+    Content,
+}
+
+/// 진행 중인 검색을 취소하기 위한 토큰
+///
+/// 복제된 토큰은 같은 상태를 공유하므로, 하나를 취소하면 모든 복제본이 취소된다.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 검색 결과 반복자를 `page_size`개씩 묶어서 반환하는 반복자
+///
+/// 페이지 중간에 오류가 발생하면 그때까지 모은 결과를 먼저 반환하고, 다음 호출에서 오류를 반환한다.
+pub struct Pages<I, E> {
+    hits: I,
+    page_size: usize,
+    error: Option<E>,
+}
+
+impl<I, E> Iterator for Pages<I, E>
+where
+    I: Iterator<Item = Result<FileHit, E>>,
+{
+    type Item = Result<Vec<FileHit>, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+
+        let mut page = Vec::with_capacity(self.page_size);
+        for hit in self.hits.by_ref() {
+            match hit {
+                Ok(hit) => page.push(hit),
+                Err(e) if page.is_empty() => return Some(Err(e)),
+                Err(e) => {
+                    self.error = Some(e);
+                    break;
+                }
+            }
+            if page.len() >= self.page_size {
+                break;
+            }
+        }
+
+        (!page.is_empty()).then_some(Ok(page))
+    }
+}
+
+/// `FileHit` 결과 반복자를 페이지 단위로 묶기 위한 확장 트레이트
+pub trait IntoPages<E>: Sized {
+    fn pages(self, page_size: usize) -> Pages<Self, E>;
+}
+
+impl<I, E> IntoPages<E> for I
+where
+    I: Iterator<Item = Result<FileHit, E>>,
+{
+    fn pages(self, page_size: usize) -> Pages<Self, E> {
+        Pages {
+            hits: self,
+            page_size: page_size.max(1),
+            error: None,
+        }
+    }
+}
+
+/// 파일 검색 결과
+#[derive(Debug, Clone)]
+pub struct FileHit {
+    /// 파일 이름 (확장자 포함)
+    pub name: String,
+    /// `file:` 접두사가 제거된 파일 경로
+    pub path: PathBuf,
+    /// 파일 크기 (byte)
+    pub size: u64,
+    /// 마지막 수정 시각
+    pub modified: SystemTime,
+    /// 확장자로 추정한 MIME 타입 (저장하기 전에 `loader::detect_mime`으로 파일 내용에 맞게 바뀜)
+    pub mime: &'static str,
+    /// 파일을 찾은 검색 소스
+    pub source: HitSource,
+    /// OS 인덱스가 제공하는 문서 속성
+    pub properties: FileProperties,
+}
+
+/// OS 인덱스가 제공하는 문서 속성 (제공하지 않는 속성은 `None`)
+#[derive(Debug, Clone, Default)]
+pub struct FileProperties {
+    /// 문서 제목
+    pub title: Option<String>,
+    /// 작성자
+    pub author: Option<String>,
+    /// 문서 종류 (예: `document`, `picture`)
+    pub kind: Option<String>,
+    /// 생성 시각
+    pub created: Option<SystemTime>,
+}
+
+impl FileProperties {
+    /// 문서 조각의 메타데이터에 추가할 (이름, 값) 목록을 만드는 함수 (생성 시각은 UTC ISO 8601 형식)
+    pub fn to_metadata(&self) -> HashMap<&'static str, String> {
+        let mut metadata = HashMap::new();
+        let fields = [
+            ("title", &self.title),
+            ("author", &self.author),
+            ("kind", &self.kind),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value.as_ref().filter(|value| !value.is_empty()) {
+                metadata.insert(name, value.clone());
+            }
+        }
+        if let Some(created) = self.created {
+            let (year, month, day, hour, minute, second) = to_utc_datetime(created);
+            metadata.insert(
+                "created",
+                format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z"),
+            );
+        }
+        metadata
+    }
+}
+
+/// 파일을 찾은 검색 소스
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HitSource {
+    /// Windows Search `SystemIndex`
+    #[cfg(target_os = "windows")]
+    WindowsSearch,
+    /// macOS Spotlight `NSMetadataQuery`
+    #[cfg(target_os = "macos")]
+    Spotlight,
+    /// Linux `locate` 데이터베이스
+    #[cfg(target_os = "linux")]
+    Locate,
+    /// `locate`를 사용할 수 없을 때의 파일 시스템 탐색
+    #[cfg(target_os = "linux")]
+    FileSystem,
+    /// `crawler` 모듈의 파일 시스템 탐색
+    Crawl,
+    /// `watch` 모듈의 파일 변경 감시
+    Watch,
+}
+
+impl FileHit {
+    /// 파일 시스템 메타데이터로부터 `FileHit`을 생성하는 함수
+    pub fn from_metadata(path: PathBuf, metadata: &std::fs::Metadata, source: HitSource) -> Self {
+        Self {
+            name: file_name(&path),
+            mime: guess_mime(&path),
+            size: metadata.len(),
+            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            path,
+            source,
+            properties: FileProperties {
+                created: metadata.created().ok(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// 파일 검색 조건
+///
+/// 각 OS 백엔드는 이 조건을 자신의 쿼리 언어로 변환하고, 결과에 `matches`를 한 번 더 적용하여
+/// 같은 조건이 모든 OS에서 같은 결과를 반환하도록 한다.
+#[derive(Debug, Clone)]
+pub struct SearchFilter {
+    /// 허용할 확장자 (`.` 없이 소문자, 비어 있으면 모든 확장자)
+    pub extensions: Vec<String>,
+    /// 최소 파일 크기 (byte, 포함)
+    pub min_size: Option<u64>,
+    /// 최대 파일 크기 (byte, 미포함)
+    pub max_size: Option<u64>,
+    /// 이 시각 이후에 수정된 파일만 포함
+    pub modified_after: Option<SystemTime>,
+    /// 이 시각 이전에 수정된 파일만 포함
+    pub modified_before: Option<SystemTime>,
+    /// 검색할 경로 (비어 있으면 OS 인덱스 전체)
+    pub path_prefixes: Vec<PathBuf>,
+}
+
+impl Default for SearchFilter {
+    fn default() -> Self {
+        Self {
+            extensions: ["pdf", "png", "jpg", "jpeg", "tif", "tiff"]
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+            min_size: None,
+            max_size: Some(10_000_000),
+            modified_after: None,
+            modified_before: None,
+            path_prefixes: Vec::new(),
+        }
+    }
+}
+
+impl SearchFilter {
+    /// 검색 결과가 조건에 해당하는지 확인하는 함수
+    pub fn matches(&self, hit: &FileHit) -> bool {
+        self.matches_extension(&hit.path)
+            && self.min_size.is_none_or(|min| hit.size >= min)
+            && self.max_size.is_none_or(|max| hit.size < max)
+            && self
+                .modified_after
+                .is_none_or(|after| hit.modified >= after)
+            && self
+                .modified_before
+                .is_none_or(|before| hit.modified < before)
+            && (self.path_prefixes.is_empty()
+                || self
+                    .path_prefixes
+                    .iter()
+                    .any(|prefix| hit.path.starts_with(prefix)))
+    }
+
+    pub fn matches_extension(&self, path: &Path) -> bool {
+        if self.extensions.is_empty() {
+            return true;
+        }
+        path.extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .is_some_and(|extension| self.extensions.contains(&extension))
+    }
+}
+
+/// 문서 내용 검색에 사용할 단어들을 검색어에서 추출하는 함수
+///
+/// 문장 부호로 단어를 나누고, 한 글자 단어와 중복된 단어는 제외한다.
+#[cfg(target_os = "macos")]
+pub(crate) fn content_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for term in query.split(|c: char| !c.is_alphanumeric()) {
+        let term = term.to_lowercase();
+        if term.chars().count() > 1 && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// `SystemTime`을 UTC 기준 `(연, 월, 일, 시, 분, 초)`로 변환하는 함수
+pub(crate) fn to_utc_datetime(time: SystemTime) -> (i64, u32, u32, u32, u32, u32) {
+    let seconds = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    let days = seconds.div_euclid(86_400);
+    let seconds_of_day = seconds.rem_euclid(86_400) as u32;
+
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+    )
+}
+
+/// OS 인덱스가 반환한 `file:` URL에서 파일 경로를 추출하는 함수
+#[cfg(target_os = "windows")]
+pub(crate) fn path_from_item_url(url: &str) -> PathBuf {
+    PathBuf::from(url.strip_prefix("file:").unwrap_or(url))
+}
+
+pub(crate) fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// 확장자로 MIME 타입을 추정하는 함수
+pub fn guess_mime(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("pdf") => "application/pdf",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("tif" | "tiff") => "image/tiff",
+        Some("bmp") => "image/bmp",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("hwp") => "application/x-hwp",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
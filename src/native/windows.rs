@@ -1,80 +1,300 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    mem::{offset_of, size_of},
+    time::{Duration, SystemTime},
+};
+
 use windows::{
-    core::{w, IUnknown, Interface, GUID, PCWSTR, PWSTR},
+    core::{w, Error, IUnknown, Interface, GUID, HRESULT, HSTRING, PWSTR},
     Win32::System::{
-        Com::{CoCreateInstance, CLSCTX_ALL, CLSCTX_INPROC_SERVER},
+        Com::{CoCreateInstance, CoTaskMemFree, CLSCTX_ALL, CLSCTX_INPROC_SERVER},
         Ole::{OleInitialize, OleUninitialize},
         Search::{
             CSearchManager, IAccessor, ICommand, ICommandText, IDBCreateCommand, IDBCreateSession,
             IDBInitialize, IDataInitialize, IRowset, ISearchCatalogManager, ISearchManager,
             ISearchQueryHelper, DBACCESSOR_ROWDATA, DBBINDING, DBMEMOWNER_CLIENTOWNED,
-            DBPARAMIO_NOTPARAM, DBPART_VALUE, DBTYPE_WSTR, DB_NULL_HCHAPTER, HACCESSOR,
-            MSDAINITIALIZE,
+            DBPARAMIO_NOTPARAM, DBPART_LENGTH, DBPART_STATUS, DBPART_VALUE,
+            DBSTATUS_E_PERMISSIONDENIED, DBSTATUS_S_OK, DBSTATUS_S_TRUNCATED, DBTYPE_BYREF,
+            DBTYPE_FILETIME, DBTYPE_UI8, DBTYPE_WSTR, DB_NULL_HCHAPTER, HACCESSOR, MSDAINITIALIZE,
         },
     },
 };
 
-pub type SearchData = (String, String, String);
+use super::{
+    file_name, guess_mime, path_from_item_url, to_utc_datetime, CancelToken, FileHit,
+    FileProperties, HitSource, QueryMode, SearchErrorKind, SearchFilter, SearchOptions,
+};
+
 pub type SearchLocalFileResult<T> = Result<T, SearchLocalFileError>;
 
 #[derive(Debug)]
 pub enum SearchLocalFileError {
-    #[allow(unused)]
-    CreateAccessor(String),
-    /// (Error Message, Cast to)
-    #[allow(unused)]
-    Cast(String, &'static str),
-    /// (Error Message, column_index, row_index)
-    #[allow(unused)]
-    GetData(String, usize, usize),
-    /// (Error Message, column_index, row_index)
-    #[allow(unused)]
-    ReleaseAccessor(String, usize, usize),
-    /// (Error Message, column_index)
-    #[allow(unused)]
-    ReleaseRows(String, usize),
-    /// (Error Message, Cast to)
-    #[allow(unused)]
-    CoCreateInstance(String, &'static str),
-    #[allow(unused)]
-    GetDataSource(String),
-    #[allow(unused)]
-    CreateSession(String),
-    #[allow(unused)]
-    CreateCommand(String),
-    #[allow(unused)]
-    DBInitialize(String),
-    /// (Error Message, SQL Query)
-    #[allow(unused)]
-    SetCommandText(String, String),
-    /// (Error Message, SQL Query)
-    #[allow(unused)]
-    Exeute(String, String),
+    CreateAccessor(Error),
+    /// (Error, Cast to)
+    Cast(Error, &'static str),
+    /// (Error, 지금까지 가져온 행의 수)
+    GetNextRows(Error, usize),
+    /// (Error, row_index)
+    GetData(Error, usize),
+    /// (Column, DBSTATUS, row_index)
+    ColumnStatus(&'static str, u32, usize),
+    /// (Error, 지금까지 가져온 행의 수)
+    ReleaseRows(Error, usize),
+    /// (Error, Interface)
+    CoCreateInstance(Error, &'static str),
+    GetDataSource(Error),
+    CreateSession(Error),
+    CreateCommand(Error),
+    DBInitialize(Error),
+    /// (Error, SQL Query)
+    SetCommandText(Error, String),
+    /// (Error, SQL Query)
+    Exeute(Error, String),
     /// (SQL Query)
-    #[allow(unused)]
     NotMatched(String),
-    #[allow(unused)]
-    GetCatalog(String),
-    #[allow(unused)]
-    GetQueryHelper(String),
-    /// (Error Message, Set Query Kind)
-    #[allow(unused)]
-    SetQuery(String, &'static str),
-    #[allow(unused)]
-    GenerateSQLFromUserQuery(String),
-    #[allow(unused)]
-    OleInitialize(String),
+    GetCatalog(Error),
+    GetQueryHelper(Error),
+    /// (Error, Set Query Kind)
+    SetQuery(Error, &'static str),
+    GenerateSQLFromUserQuery(Error),
+    OleInitialize(Error),
+}
+
+impl SearchLocalFileError {
+    /// 플랫폼과 관계없는 오류 종류를 반환하는 함수
+    pub fn kind(&self) -> SearchErrorKind {
+        if std::error::Error::source(self)
+            .and_then(|source| source.downcast_ref::<Error>())
+            .is_some_and(|e| e.code() == E_ACCESSDENIED)
+        {
+            return SearchErrorKind::PermissionDenied;
+        }
+
+        match self {
+            Self::ColumnStatus(_, status, _) if *status == DBSTATUS_E_PERMISSIONDENIED.0 as u32 => {
+                SearchErrorKind::PermissionDenied
+            }
+            // Windows Search 서비스가 꺼져 있거나 설치되지 않은 경우
+            Self::OleInitialize(_)
+            | Self::CoCreateInstance(..)
+            | Self::GetDataSource(_)
+            | Self::DBInitialize(_)
+            | Self::CreateSession(_)
+            | Self::CreateCommand(_)
+            | Self::GetCatalog(_)
+            | Self::GetQueryHelper(_) => SearchErrorKind::IndexUnavailable,
+            Self::SetQuery(..)
+            | Self::GenerateSQLFromUserQuery(_)
+            | Self::SetCommandText(..)
+            | Self::Exeute(..) => SearchErrorKind::QuerySyntax,
+            _ => SearchErrorKind::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for SearchLocalFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.kind())?;
+        match self {
+            Self::CreateAccessor(_) => write!(f, "failed to create accessor"),
+            Self::Cast(_, target) => write!(f, "failed to cast to {target}"),
+            Self::GetNextRows(_, count) => write!(f, "failed to fetch rows after {count} rows"),
+            Self::GetData(_, row) => write!(f, "failed to get data in row {row}"),
+            Self::ColumnStatus(column, status, row) => {
+                write!(f, "{column} in row {row} returned DBSTATUS {status}")
+            }
+            Self::ReleaseRows(_, count) => write!(f, "failed to release rows after {count} rows"),
+            Self::CoCreateInstance(_, target) => write!(f, "failed to create {target}"),
+            Self::GetDataSource(_) => write!(f, "failed to get data source"),
+            Self::CreateSession(_) => write!(f, "failed to create session"),
+            Self::CreateCommand(_) => write!(f, "failed to create command"),
+            Self::DBInitialize(_) => write!(f, "failed to initialize data source"),
+            Self::SetCommandText(_, query) => write!(f, "failed to set command text `{query}`"),
+            Self::Exeute(_, query) => write!(f, "failed to execute `{query}`"),
+            Self::NotMatched(query) => write!(f, "no rowset returned for `{query}`"),
+            Self::GetCatalog(_) => write!(f, "failed to get SystemIndex catalog"),
+            Self::GetQueryHelper(_) => write!(f, "failed to get query helper"),
+            Self::SetQuery(_, kind) => write!(f, "failed to set {kind}"),
+            Self::GenerateSQLFromUserQuery(_) => write!(f, "failed to generate SQL from query"),
+            Self::OleInitialize(_) => write!(f, "failed to initialize OLE"),
+        }
+    }
 }
 
+impl std::error::Error for SearchLocalFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::CreateAccessor(e)
+            | Self::Cast(e, _)
+            | Self::GetNextRows(e, _)
+            | Self::GetData(e, _)
+            | Self::ReleaseRows(e, _)
+            | Self::CoCreateInstance(e, _)
+            | Self::GetDataSource(e)
+            | Self::CreateSession(e)
+            | Self::CreateCommand(e)
+            | Self::DBInitialize(e)
+            | Self::SetCommandText(e, _)
+            | Self::Exeute(e, _)
+            | Self::GetCatalog(e)
+            | Self::GetQueryHelper(e)
+            | Self::SetQuery(e, _)
+            | Self::GenerateSQLFromUserQuery(e)
+            | Self::OleInitialize(e) => Some(e),
+            Self::ColumnStatus(..) | Self::NotMatched(_) => None,
+        }
+    }
+}
+
+/// `E_ACCESSDENIED`
+const E_ACCESSDENIED: HRESULT = HRESULT(0x80070005_u32 as i32);
+
 /// `query` 문자열로 파일 시스템의 인덱스를 검색하는 함수
-pub fn search_local_files_by_query(query: String) -> SearchLocalFileResult<Vec<SearchData>> {
+///
+/// 결과는 `options.page_size`개씩 가져오는 `SearchHits` 반복자로 반환된다.
+pub fn search_local_files(
+    query: String,
+    filter: &SearchFilter,
+    options: &SearchOptions,
+) -> SearchLocalFileResult<SearchHits> {
     unsafe {
-        OleInitialize(None).map_err(|e| SearchLocalFileError::OleInitialize(e.to_string()))?;
-        let sql_query = create_search_query(query)?;
+        OleInitialize(None).map_err(SearchLocalFileError::OleInitialize)?;
+        // 반환 전에 실패하더라도 `Drop`에서 `OleUninitialize`가 호출되도록 먼저 생성
+        let mut hits = SearchHits {
+            rowset: None,
+            accessor: None,
+            accessor_handle: None,
+            filter: filter.clone(),
+            page_size: options.page_size.max(1),
+            remaining: options.limit,
+            cancel: options.cancel.clone(),
+            buffer: VecDeque::new(),
+            row_count: 0,
+            finished: false,
+        };
+        let sql_query = create_search_query(query, filter, options)?;
         let rowset = execute_search_query(sql_query)?;
-        let result = process_query_data(&rowset)?;
-        OleUninitialize();
+        let accessor: IAccessor = rowset
+            .cast()
+            .map_err(|e| SearchLocalFileError::Cast(e, "IAccessor"))?;
+        hits.accessor_handle = Some(create_accessor_handle(&accessor)?);
+        hits.accessor = Some(accessor);
+        hits.rowset = Some(rowset);
+
+        Ok(hits)
+    }
+}
+
+/// Windows Search 쿼리 결과를 페이지 단위로 가져오는 반복자
+pub struct SearchHits {
+    rowset: Option<IRowset>,
+    accessor: Option<IAccessor>,
+    /// `COLUMNS`를 한 번에 가져오는 접근자 핸들
+    accessor_handle: Option<HACCESSOR>,
+    filter: SearchFilter,
+    page_size: usize,
+    /// 남은 최대 결과 수 (`None`이면 제한 없음)
+    remaining: Option<usize>,
+    cancel: CancelToken,
+    /// 가져온 행들 (필수 컬럼을 읽지 못한 행은 오류로 저장)
+    buffer: VecDeque<SearchLocalFileResult<FileHit>>,
+    /// 지금까지 가져온 행의 수
+    row_count: usize,
+    finished: bool,
+}
+
+impl SearchHits {
+    /// 다음 페이지의 행들을 가져와 `buffer`에 저장하는 함수
+    unsafe fn fetch_page(&mut self) -> SearchLocalFileResult<()> {
+        let (Some(rowset), Some(accessor_handle)) = (&self.rowset, self.accessor_handle) else {
+            self.finished = true;
+            return Ok(());
+        };
+
+        // `GetNextRows`는 `prghrows`의 길이를 가져올 행의 수로 사용하고, 첫 번째 원소를
+        // 행 핸들 배열의 포인터로 사용하므로 미리 할당한 배열의 포인터를 넣어둠
+        let mut row_handles = vec![0usize; self.page_size];
+        let mut row_handles_pointer = vec![std::ptr::null_mut(); self.page_size];
+        row_handles_pointer[0] = row_handles.as_mut_ptr();
+
+        let mut rows_fetched = 0;
+        rowset
+            .GetNextRows(
+                DB_NULL_HCHAPTER as usize,
+                0,
+                &mut rows_fetched,
+                &mut row_handles_pointer,
+            )
+            .map_err(|e| SearchLocalFileError::GetNextRows(e, self.row_count))?;
+        // 결과의 끝에 도달하면 성공 코드인 `DB_S_ENDOFROWSET`과 함께 요청보다 적은 행이 반환됨
+        if rows_fetched < self.page_size {
+            self.finished = true;
+        }
+        if rows_fetched == 0 {
+            return Ok(());
+        }
+
+        let rows = row_handles[..rows_fetched]
+            .iter()
+            .enumerate()
+            .map(|(i, row_handle)| {
+                process_row_data(rowset, accessor_handle, *row_handle, self.row_count + i)
+            })
+            .collect::<Vec<_>>();
+        self.row_count += rows_fetched;
+        rowset
+            .ReleaseRows(
+                rows_fetched,
+                row_handles.as_ptr(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+            .map_err(|e| SearchLocalFileError::ReleaseRows(e, self.row_count))?;
 
-        Ok(result)
+        // 인덱스에 저장된 값과 파일 시스템의 값이 다를 수 있으므로 한 번 더 확인
+        // 읽지 못한 행은 오류로 반환하고 나머지 행과 다음 페이지는 계속 가져옴
+        self.buffer.extend(rows.into_iter().filter(|hit| match hit {
+            Ok(hit) => self.filter.matches(hit),
+            Err(_) => true,
+        }));
+        Ok(())
+    }
+}
+
+impl Iterator for SearchHits {
+    type Item = SearchLocalFileResult<FileHit>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) || self.cancel.is_cancelled() {
+            return None;
+        }
+        while self.buffer.is_empty() && !self.finished {
+            if let Err(e) = unsafe { self.fetch_page() } {
+                self.finished = true;
+                return Some(Err(e));
+            }
+        }
+
+        let hit = self.buffer.pop_front()?;
+        if hit.is_ok() {
+            if let Some(remaining) = self.remaining.as_mut() {
+                *remaining -= 1;
+            }
+        }
+        Some(hit)
+    }
+}
+
+impl Drop for SearchHits {
+    fn drop(&mut self) {
+        // COM 객체를 먼저 해제한 후 OLE를 종료
+        if let (Some(accessor), Some(accessor_handle)) = (&self.accessor, self.accessor_handle) {
+            let _ = unsafe { accessor.ReleaseAccessor(accessor_handle, None) };
+        }
+        self.accessor.take();
+        self.rowset.take();
+        unsafe { OleUninitialize() };
     }
 }
 
@@ -86,109 +306,262 @@ const DBGUID_DEFAULT: GUID = GUID {
     data4: [0xad, 0xe5, 0x00, 0xaa, 0x00, 0x44, 0x77, 0x3d],
 };
 
-/// 쿼리 결과에 해당하는 데이터에 접근하기 위한 핸들을 생성하는 함수
-unsafe fn create_accessor_handle(
-    accessor: &IAccessor,
-    index: usize,
-) -> SearchLocalFileResult<HACCESSOR> {
-    let bindings = DBBINDING {
-        iOrdinal: index,
-        obValue: 0,
-        obStatus: 0,
-        obLength: 0,
-        dwPart: DBPART_VALUE.0 as u32,
-        dwMemOwner: DBMEMOWNER_CLIENTOWNED.0 as u32,
-        eParamIO: DBPARAMIO_NOTPARAM.0 as u32,
-        cbMaxLen: 512,
-        dwFlags: 0,
-        wType: DBTYPE_WSTR.0 as u16,
-        bPrecision: 0,
-        bScale: 0,
-        ..Default::default()
-    };
-    let mut status = 0;
+/// 쿼리 결과에서 가져올 속성 컬럼
+///
+/// 컬럼의 순서가 `SELECT` 절과 `DBBINDING`의 순서가 된다.
+/// https://learn.microsoft.com/ko-kr/windows/win32/properties/core-bumper
+const COLUMNS: [Column; 8] = [
+    Column::required("System.ItemUrl", ColumnType::Text),
+    Column::optional("System.FileName", ColumnType::Text),
+    Column::optional("System.Size", ColumnType::UInt64),
+    Column::optional("System.DateModified", ColumnType::FileTime),
+    Column::optional("System.DateCreated", ColumnType::FileTime),
+    Column::optional("System.Author", ColumnType::Text),
+    Column::optional("System.Title", ColumnType::Text),
+    Column::optional("System.Kind", ColumnType::Text),
+];
+
+/// Windows Search 속성 컬럼
+struct Column {
+    name: &'static str,
+    column_type: ColumnType,
+    /// 값을 가져오지 못하면 행 전체를 오류로 처리하는지 여부
+    required: bool,
+}
+
+impl Column {
+    const fn required(name: &'static str, column_type: ColumnType) -> Self {
+        Self {
+            name,
+            column_type,
+            required: true,
+        }
+    }
+
+    const fn optional(name: &'static str, column_type: ColumnType) -> Self {
+        Self {
+            name,
+            column_type,
+            required: false,
+        }
+    }
+}
+
+/// 컬럼을 바인딩할 DB 타입
+#[derive(Clone, Copy)]
+enum ColumnType {
+    /// `DBTYPE_WSTR | DBTYPE_BYREF` (길이 제한 없이 provider가 할당한 문자열)
+    Text,
+    /// `DBTYPE_UI8`
+    UInt64,
+    /// `DBTYPE_FILETIME`
+    FileTime,
+}
+
+impl ColumnType {
+    fn db_type(self) -> u16 {
+        match self {
+            Self::Text => (DBTYPE_WSTR.0 | DBTYPE_BYREF.0) as u16,
+            Self::UInt64 => DBTYPE_UI8.0 as u16,
+            Self::FileTime => DBTYPE_FILETIME.0 as u16,
+        }
+    }
+}
+
+/// `GetData`가 한 컬럼의 값을 기록하는 버퍼
+///
+/// 모든 컬럼 타입의 값(문자열 포인터, `u64`, `FILETIME`)이 8 byte이므로 같은 구조를 사용한다.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct ColumnData {
+    value: u64,
+    /// 문자열의 길이 (byte, null 문자 제외)
+    length: usize,
+    /// `DBSTATUS`
+    status: u32,
+}
+
+/// 컬럼에서 읽은 값
+enum ColumnValue {
+    Null,
+    Text(String),
+    UInt64(u64),
+    Time(SystemTime),
+}
+
+impl ColumnValue {
+    fn into_text(self) -> Option<String> {
+        match self {
+            Self::Text(text) if !text.is_empty() => Some(text),
+            _ => None,
+        }
+    }
+
+    fn into_u64(self) -> Option<u64> {
+        match self {
+            Self::UInt64(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn into_time(self) -> Option<SystemTime> {
+        match self {
+            Self::Time(time) => Some(time),
+            _ => None,
+        }
+    }
+}
+
+/// `COLUMNS`를 한 행씩 `ColumnData` 배열로 가져오는 접근자 핸들을 생성하는 함수
+unsafe fn create_accessor_handle(accessor: &IAccessor) -> SearchLocalFileResult<HACCESSOR> {
+    let bindings = COLUMNS
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let offset = i * size_of::<ColumnData>();
+            DBBINDING {
+                iOrdinal: i + 1,
+                obValue: offset + offset_of!(ColumnData, value),
+                obLength: offset + offset_of!(ColumnData, length),
+                obStatus: offset + offset_of!(ColumnData, status),
+                dwPart: (DBPART_VALUE.0 | DBPART_LENGTH.0 | DBPART_STATUS.0) as u32,
+                dwMemOwner: DBMEMOWNER_CLIENTOWNED.0 as u32,
+                eParamIO: DBPARAMIO_NOTPARAM.0 as u32,
+                cbMaxLen: size_of::<u64>(),
+                dwFlags: 0,
+                wType: column.column_type.db_type(),
+                bPrecision: 0,
+                bScale: 0,
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut status = vec![0u32; bindings.len()];
     let mut accessor_handle = HACCESSOR::default();
     accessor
         .CreateAccessor(
             DBACCESSOR_ROWDATA.0 as u32,
-            1,
-            &bindings,
-            0,
+            bindings.len(),
+            bindings.as_ptr(),
+            COLUMNS.len() * size_of::<ColumnData>(),
             &mut accessor_handle,
-            Some(&mut status),
+            Some(status.as_mut_ptr()),
         )
-        .map_err(|e| SearchLocalFileError::CreateAccessor(e.to_string()))?;
+        .map_err(SearchLocalFileError::CreateAccessor)?;
 
     Ok(accessor_handle)
 }
 
-/// Query로 나온 결과로부터 데이터를 추출하는 함수
-unsafe fn process_query_data(rowset: &IRowset) -> SearchLocalFileResult<Vec<SearchData>> {
-    let accessor: IAccessor = rowset
-        .cast()
-        .map_err(|e| SearchLocalFileError::Cast(e.to_string(), "IAccessor"))?;
+/// Query로 나온 결과의 한 행으로부터 데이터를 추출하는 함수
+unsafe fn process_row_data(
+    rowset: &IRowset,
+    accessor_handle: HACCESSOR,
+    row_handle: usize,
+    row_index: usize,
+) -> SearchLocalFileResult<FileHit> {
+    let mut row = [ColumnData::default(); COLUMNS.len()];
+    // 일부 컬럼만 실패한 경우 `DB_S_ERRORSOCCURRED`가 반환되므로 컬럼별 `status`를 따로 확인
+    rowset
+        .GetData(row_handle, accessor_handle, row.as_mut_ptr() as *mut _)
+        .map_err(|e| SearchLocalFileError::GetData(e, row_index))?;
 
-    let mut output = Vec::new();
-    let mut count = 0;
-    loop {
-        let mut rows_fetched = 0;
-        let mut row_handles = [std::ptr::null_mut(); 1];
-        let result = rowset.GetNextRows(
-            DB_NULL_HCHAPTER as usize,
-            0,
-            &mut rows_fetched,
-            &mut row_handles,
-        );
-        if result.is_err() {
-            break;
-        }
-        if rows_fetched == 0 {
-            break;
-        }
+    // 오류를 반환하기 전에 provider가 할당한 문자열을 모두 해제하도록 먼저 읽음
+    let values = COLUMNS
+        .iter()
+        .zip(&row)
+        .map(|(column, data)| read_column(column.column_type, data))
+        .collect::<Vec<_>>();
+    if let Some((column, data)) = COLUMNS
+        .iter()
+        .zip(&row)
+        .find(|(column, data)| column.required && !is_status_ok(data.status))
+    {
+        return Err(SearchLocalFileError::ColumnStatus(
+            column.name,
+            data.status,
+            row_index,
+        ));
+    }
 
-        let mut data = Vec::new();
-
-        for i in 0..3 {
-            let mut item_name = [0u16; 512];
-
-            let accessor_handle = create_accessor_handle(&accessor, i + 1)?;
-            rowset
-                .GetData(
-                    *row_handles[0],
-                    accessor_handle,
-                    item_name.as_mut_ptr() as *mut _,
-                )
-                .map_err(|e| SearchLocalFileError::GetData(e.to_string(), count, i))?;
-            let name = String::from_utf16_lossy(&item_name);
-            // Remove null characters
-            data.push(name.trim_end_matches('\u{0000}').to_string());
-
-            accessor
-                .ReleaseAccessor(accessor_handle, None)
-                .map_err(|e| SearchLocalFileError::ReleaseAccessor(e.to_string(), count, i))?;
-        }
+    Ok(create_file_hit(values))
+}
 
-        output.push((data[0].clone(), data[1].clone(), data[2].clone()));
+fn is_status_ok(status: u32) -> bool {
+    status == DBSTATUS_S_OK.0 as u32 || status == DBSTATUS_S_TRUNCATED.0 as u32
+}
 
-        count += 1;
-        rowset
-            .ReleaseRows(
-                1,
-                row_handles[0],
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
-            )
-            .map_err(|e| SearchLocalFileError::ReleaseRows(e.to_string(), count))?;
+/// `ColumnData`의 값을 읽고, provider가 할당한 문자열을 해제하는 함수
+///
+/// 값이 없거나 (`DBSTATUS_S_ISNULL`) 변환할 수 없는 경우 (`DBSTATUS_E_*`) `ColumnValue::Null`을 반환한다.
+unsafe fn read_column(column_type: ColumnType, data: &ColumnData) -> ColumnValue {
+    if !is_status_ok(data.status) {
+        return ColumnValue::Null;
     }
 
-    Ok(output)
+    match column_type {
+        ColumnType::Text => {
+            let pointer = data.value as usize as *mut u16;
+            if pointer.is_null() {
+                return ColumnValue::Null;
+            }
+            let text = std::slice::from_raw_parts(pointer, data.length / size_of::<u16>());
+            let text = String::from_utf16_lossy(text);
+            CoTaskMemFree(Some(pointer as *const _));
+            ColumnValue::Text(text)
+        }
+        ColumnType::UInt64 => ColumnValue::UInt64(data.value),
+        ColumnType::FileTime => ColumnValue::Time(filetime_to_system_time(data.value)),
+    }
+}
+
+/// `FILETIME` (1601-01-01부터의 100ns 단위)을 `SystemTime`으로 변환하는 함수
+fn filetime_to_system_time(filetime: u64) -> SystemTime {
+    const UNIX_EPOCH_FILETIME: u64 = 116_444_736_000_000_000;
+    let nanos = filetime
+        .saturating_sub(UNIX_EPOCH_FILETIME)
+        .saturating_mul(100);
+    SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos)
+}
+
+/// `COLUMNS` 순서의 컬럼 값으로 `FileHit`을 생성하는 함수
+fn create_file_hit(values: Vec<ColumnValue>) -> FileHit {
+    let mut values = COLUMNS
+        .iter()
+        .map(|column| column.name)
+        .zip(values)
+        .collect::<HashMap<_, _>>();
+    let mut take = |name| values.remove(name).unwrap_or(ColumnValue::Null);
+
+    let path = path_from_item_url(&take("System.ItemUrl").into_text().unwrap_or_default());
+    // 인덱스에 수정 시각이 없으면 파일 시스템에서 가져옴
+    let modified = take("System.DateModified").into_time().unwrap_or_else(|| {
+        std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    });
+
+    FileHit {
+        name: take("System.FileName")
+            .into_text()
+            .unwrap_or_else(|| file_name(&path)),
+        mime: guess_mime(&path),
+        size: take("System.Size").into_u64().unwrap_or(0),
+        modified,
+        properties: FileProperties {
+            title: take("System.Title").into_text(),
+            author: take("System.Author").into_text(),
+            kind: take("System.Kind").into_text(),
+            created: take("System.DateCreated").into_time(),
+        },
+        path,
+        source: HitSource::WindowsSearch,
+    }
 }
 
 /// OLEDB를 초기화 객체를 생성하는 함수
 unsafe fn create_db_initialize() -> SearchLocalFileResult<IDBInitialize> {
     let data_init: IDataInitialize = CoCreateInstance(&MSDAINITIALIZE, None, CLSCTX_INPROC_SERVER)
-        .map_err(|e| SearchLocalFileError::CoCreateInstance(e.to_string(), "IDataInitialize"))?;
+        .map_err(|e| SearchLocalFileError::CoCreateInstance(e, "IDataInitialize"))?;
 
     let mut unknown: Option<IUnknown> = None;
     data_init
@@ -199,30 +572,30 @@ unsafe fn create_db_initialize() -> SearchLocalFileResult<IDBInitialize> {
             &IDBInitialize::IID,
             &mut unknown as *mut _ as *mut _,
         )
-        .map_err(|e| SearchLocalFileError::GetDataSource(e.to_string()))?;
+        .map_err(SearchLocalFileError::GetDataSource)?;
 
     Ok(unknown
         .unwrap()
         .cast()
-        .map_err(|e| SearchLocalFileError::Cast(e.to_string(), "IDBInitialize"))?)
+        .map_err(|e| SearchLocalFileError::Cast(e, "IDBInitialize"))?)
 }
 
 /// SQL 쿼리를 실행하는 객체를 생성하는 함수
 unsafe fn create_command(db_init: IDBInitialize) -> SearchLocalFileResult<ICommandText> {
     let db_create_session: IDBCreateSession = db_init
         .cast()
-        .map_err(|e| SearchLocalFileError::Cast(e.to_string(), "IDBCreateSession"))?;
+        .map_err(|e| SearchLocalFileError::Cast(e, "IDBCreateSession"))?;
     let session: IUnknown = db_create_session
         .CreateSession(None, &IUnknown::IID)
-        .map_err(|e| SearchLocalFileError::CreateSession(e.to_string()))?;
+        .map_err(SearchLocalFileError::CreateSession)?;
     let db_create_command: IDBCreateCommand = session
         .cast()
-        .map_err(|e| SearchLocalFileError::Cast(e.to_string(), "IDBCreateCommand"))?;
+        .map_err(|e| SearchLocalFileError::Cast(e, "IDBCreateCommand"))?;
     Ok(db_create_command
         .CreateCommand(None, &ICommand::IID)
-        .map_err(|e| SearchLocalFileError::CreateCommand(e.to_string()))?
+        .map_err(SearchLocalFileError::CreateCommand)?
         .cast()
-        .map_err(|e| SearchLocalFileError::Cast(e.to_string(), "ICommandText"))?)
+        .map_err(|e| SearchLocalFileError::Cast(e, "ICommandText"))?)
 }
 
 /// SQL 쿼리를 실행하는 함수
@@ -230,15 +603,13 @@ unsafe fn execute_search_query(sql_query: PWSTR) -> SearchLocalFileResult<IRowse
     let db_init = create_db_initialize()?;
     db_init
         .Initialize()
-        .map_err(|e| SearchLocalFileError::DBInitialize(e.to_string()))?;
+        .map_err(SearchLocalFileError::DBInitialize)?;
     let command = create_command(db_init)?;
 
     // Set the command text
     command
         .SetCommandText(&DBGUID_DEFAULT, sql_query)
-        .map_err(|e| {
-            SearchLocalFileError::SetCommandText(e.to_string(), pwstr_to_string(sql_query))
-        })?;
+        .map_err(|e| SearchLocalFileError::SetCommandText(e, pwstr_to_string(sql_query)))?;
 
     // Execute the command
     let mut rowset: Option<IRowset> = None;
@@ -250,44 +621,123 @@ unsafe fn execute_search_query(sql_query: PWSTR) -> SearchLocalFileResult<IRowse
             None,
             Some(&mut rowset as *mut _ as *mut _),
         )
-        .map_err(|e| SearchLocalFileError::Exeute(e.to_string(), pwstr_to_string(sql_query)))?;
+        .map_err(|e| SearchLocalFileError::Exeute(e, pwstr_to_string(sql_query)))?;
     rowset.ok_or(SearchLocalFileError::NotMatched(pwstr_to_string(sql_query)))
 }
 
 /// OLEDB가 이해할 수 있는 SQL 쿼리를 생성하는 함수
-unsafe fn create_search_query(query_file_name: String) -> SearchLocalFileResult<PWSTR> {
+unsafe fn create_search_query(
+    query_file_name: String,
+    filter: &SearchFilter,
+    options: &SearchOptions,
+) -> SearchLocalFileResult<PWSTR> {
     let search_manager: ISearchManager = CoCreateInstance(&CSearchManager, None, CLSCTX_ALL)
-        .map_err(|e| SearchLocalFileError::CoCreateInstance(e.to_string(), "ISearchManager"))?;
+        .map_err(|e| SearchLocalFileError::CoCreateInstance(e, "ISearchManager"))?;
     let catalog: ISearchCatalogManager = search_manager
         .GetCatalog(w!("SystemIndex"))
-        .map_err(|e| SearchLocalFileError::GetCatalog(e.to_string()))?;
+        .map_err(SearchLocalFileError::GetCatalog)?;
     let query_helper: ISearchQueryHelper = catalog
         .GetQueryHelper()
-        .map_err(|e| SearchLocalFileError::GetQueryHelper(e.to_string()))?;
+        .map_err(SearchLocalFileError::GetQueryHelper)?;
 
     // Windows Search API에서 접근 가능한 키워드 문서
     // https://learn.microsoft.com/ko-kr/windows/win32/properties/core-bumper
+    let columns = COLUMNS
+        .iter()
+        .map(|column| column.name)
+        .collect::<Vec<_>>()
+        .join(", ");
     query_helper
-        .SetQuerySelectColumns(w!("System.FileName, System.ItemUrl, System.Size"))
-        .map_err(|e| SearchLocalFileError::SetQuery(e.to_string(), "QuerySelectColumns"))?;
-    query_helper
-        .SetQueryWhereRestrictions(w!(
-            "AND (System.FileExtension = '.pdf' OR System.FileExtension = '.png') AND System.Size < 10000000"
-        ))
-        .map_err(|e| SearchLocalFileError::SetQuery(e.to_string(), "QueryWhereRestrictions"))?;
+        .SetQuerySelectColumns(&HSTRING::from(columns))
+        .map_err(|e| SearchLocalFileError::SetQuery(e, "QuerySelectColumns"))?;
+    let mut restrictions = create_where_restrictions(filter);
+    let user_query = match options.mode {
+        QueryMode::FileName => query_file_name,
+        QueryMode::Content => {
+            // 질문 문장은 AQS 문법으로 해석하지 않고 `FREETEXT`로 문서 내용과 비교
+            restrictions.push_str(&format!(
+                " AND FREETEXT(System.Search.Contents, '{}')",
+                escape_sql(&query_file_name)
+            ));
+            query_helper
+                .SetQuerySorting(w!("System.Search.Rank DESC"))
+                .map_err(|e| SearchLocalFileError::SetQuery(e, "QuerySorting"))?;
+            String::new()
+        }
+    };
     query_helper
-        .SetQueryMaxResults(100)
-        .map_err(|e| SearchLocalFileError::SetQuery(e.to_string(), "QueryMaxResults"))?;
+        .SetQueryWhereRestrictions(&HSTRING::from(restrictions))
+        .map_err(|e| SearchLocalFileError::SetQuery(e, "QueryWhereRestrictions"))?;
+    if let Some(limit) = options.limit {
+        query_helper
+            .SetQueryMaxResults(i32::try_from(limit).unwrap_or(i32::MAX))
+            .map_err(|e| SearchLocalFileError::SetQuery(e, "QueryMaxResults"))?;
+    }
 
     query_helper
-        .GenerateSQLFromUserQuery(string_to_pcwstr(query_file_name))
-        .map_err(|e| SearchLocalFileError::GenerateSQLFromUserQuery(e.to_string()))
+        .GenerateSQLFromUserQuery(&HSTRING::from(user_query))
+        .map_err(SearchLocalFileError::GenerateSQLFromUserQuery)
+}
+
+/// `SearchFilter`를 Windows Search SQL의 `WHERE` 조건으로 변환하는 함수
+///
+/// `ISearchQueryHelper`가 생성하는 조건 뒤에 붙으므로 각 조건은 `AND`로 시작한다.
+fn create_where_restrictions(filter: &SearchFilter) -> String {
+    let mut restrictions = Vec::new();
+    if !filter.extensions.is_empty() {
+        let extensions = filter
+            .extensions
+            .iter()
+            .map(|extension| format!("System.FileExtension = '.{}'", escape_sql(extension)))
+            .collect::<Vec<_>>();
+        restrictions.push(format!("({})", extensions.join(" OR ")));
+    }
+    if let Some(min_size) = filter.min_size {
+        restrictions.push(format!("System.Size >= {min_size}"));
+    }
+    if let Some(max_size) = filter.max_size {
+        restrictions.push(format!("System.Size < {max_size}"));
+    }
+    if let Some(after) = filter.modified_after {
+        restrictions.push(format!(
+            "System.DateModified >= '{}'",
+            format_sql_datetime(after)
+        ));
+    }
+    if let Some(before) = filter.modified_before {
+        restrictions.push(format!(
+            "System.DateModified < '{}'",
+            format_sql_datetime(before)
+        ));
+    }
+    if !filter.path_prefixes.is_empty() {
+        let scopes = filter
+            .path_prefixes
+            .iter()
+            .map(|prefix| {
+                // `System.ItemUrl`과 같이 `/`로 구분된 경로를 사용
+                let prefix = prefix.to_string_lossy().replace('\\', "/");
+                format!("SCOPE = 'file:{}'", escape_sql(&prefix))
+            })
+            .collect::<Vec<_>>();
+        restrictions.push(format!("({})", scopes.join(" OR ")));
+    }
+
+    restrictions
+        .iter()
+        .map(|restriction| format!("AND {restriction}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Windows Search SQL의 날짜 형식(`YYYY/MM/DD hh:mm:ss`, UTC)으로 변환하는 함수
+fn format_sql_datetime(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = to_utc_datetime(time);
+    format!("{year:04}/{month:02}/{day:02} {hour:02}:{minute:02}:{second:02}")
 }
 
-fn string_to_pcwstr(str: String) -> PCWSTR {
-    let mut v: Vec<u16> = str.encode_utf16().collect();
-    v.push(0);
-    PCWSTR::from_raw(v.as_ptr())
+fn escape_sql(value: &str) -> String {
+    value.replace('\'', "''")
 }
 
 unsafe fn pwstr_to_string(pw_str: PWSTR) -> String {
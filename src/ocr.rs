@@ -1,6 +1,9 @@
 use std::{collections::HashMap, io::Cursor};
 
-use rusty_tesseract::{image::ImageReader, Args, Image};
+use rusty_tesseract::{
+    image::{DynamicImage, ImageReader},
+    Args, Image,
+};
 
 // pub fn ocr_by_path(file_path: &std::path::Path) -> String {
 //     let img = image_reader.decode().unwrap();
@@ -16,15 +19,27 @@ use rusty_tesseract::{image::ImageReader, Args, Image};
 //     rusty_tesseract::image_to_string(&img, &args).unwrap_or(String::new())
 // }
 
+/// 이미지 데이터의 글자를 OCR하는 함수
+///
+/// 형식을 판별할 수 없거나 읽을 수 없는 이미지는 빈 문자열을 반환한다.
 pub fn ocr_by_buffer(data: &[u8]) -> String {
-    let image_reader = ImageReader::new(Cursor::new(&data))
-        .with_guessed_format()
-        .unwrap();
+    let Ok(image_reader) = ImageReader::new(Cursor::new(&data)).with_guessed_format() else {
+        return String::new();
+    };
     if image_reader.format().is_none() {
         return String::new();
     }
-    let img = image_reader.decode().unwrap();
+    let Ok(img) = image_reader.decode() else {
+        return String::new();
+    };
 
+    ocr_image(&img)
+}
+
+/// 디코딩한 이미지의 글자를 OCR하는 함수
+///
+/// 읽을 수 없는 이미지는 빈 문자열을 반환한다.
+pub fn ocr_image(img: &DynamicImage) -> String {
     let args = Args {
         lang: "kor+eng".to_string(),
         config_variables: HashMap::new(),
@@ -32,6 +47,8 @@ pub fn ocr_by_buffer(data: &[u8]) -> String {
         psm: None,
         oem: None,
     };
-    let img = Image::from_dynamic_image(&img).expect("Failed to convert image");
-    rusty_tesseract::image_to_string(&img, &args).unwrap_or(String::new())
+    let Ok(img) = Image::from_dynamic_image(img) else {
+        return String::new();
+    };
+    rusty_tesseract::image_to_string(&img, &args).unwrap_or_default()
 }
@@ -1,97 +1,886 @@
-use pdf::{content::Op, enc::StreamFilter, file::FileOptions, object::Resolve};
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+};
 
-pub fn read_pdf_file(file_path: &std::path::Path) -> Result<String, String> {
-    std::panic::catch_unwind(|| pdf_extract::extract_text(file_path).map_err(|e| e.to_string()))
-        .unwrap_or_else(|_| Err("Catched panic by pdf_extract".to_string()))
+use pdf::{
+    content::{Matrix, Op, TextDrawAdjusted},
+    enc::StreamFilter,
+    file::FileOptions,
+    object::{ColorSpace, ImageDict, Resolve, XObject},
+};
+use rusty_tesseract::image::{DynamicImage, GrayImage, RgbImage};
+
+use crate::{
+    layout::{self, TextRun},
+    table::{self, Ruling, Table},
+};
+
+/// 공백을 제외한 글자가 이보다 적은 페이지는 이미지를 OCR하여 글자를 보충
+const MIN_PAGE_TEXT_LENGTH: usize = 32;
+/// 글꼴 정보 없이 글자 폭을 추정할 때 사용하는 글자 크기 대비 폭 (한글, 한자 등은 2배)
+const GLYPH_WIDTH: f32 = 0.5;
+/// `TJ`의 간격 조정값 중 띄어쓰기로 보는 최소 간격 (글자 크기 대비)
+const ADJUSTMENT_SPACE: f32 = 0.2;
+
+/// 페이지 글자의 일부분이 어디에서 추출되었는지
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    /// PDF 내용 스트림의 글자
+    Text,
+    /// 페이지에 포함된 이미지를 OCR한 글자
+    Ocr,
+    /// 페이지에서 찾은 표를 행마다 한 줄로 쓴 Markdown 표 (`Page::tables`)
+    Table,
+}
+
+impl SpanKind {
+    /// `src-python/core/loader/pdf.py`의 `get_document_from_pages`에 전달하는 이름
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Ocr => "ocr",
+            Self::Table => "table",
+        }
+    }
+}
+
+/// 페이지 글자의 일부분
+///
+/// `start`와 `end`는 `Page::text`에서의 글자(`char`) 위치로, `Python`의 문서 조각
+/// `start_index`와 같은 기준이다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub kind: SpanKind,
+}
+
+/// PDF에서 추출한 페이지 하나
+#[derive(Debug, Clone, Default)]
+pub struct Page {
+    /// 1부터 시작하는 페이지 번호
+    pub number: u32,
+    pub text: String,
+    /// `text`를 빈틈없이 나눈 부분들 (글자가 없으면 비어 있음)
+    pub spans: Vec<Span>,
+    /// 페이지에서 찾은 표 (`text`에는 `SpanKind::Table`로 포함)
+    pub tables: Vec<Table>,
+}
+
+impl Page {
+    /// 추출한 글자 전체가 PDF 내용 스트림에서 나온 페이지를 만드는 함수
+    pub fn new(number: u32, text: String) -> Self {
+        let length = text.chars().count();
+        let spans = if length == 0 {
+            vec![]
+        } else {
+            vec![Span {
+                start: 0,
+                end: length,
+                kind: SpanKind::Text,
+            }]
+        };
+
+        Self {
+            number,
+            text,
+            spans,
+            tables: vec![],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    /// 추출한 글자가 적어 이미지를 OCR해야 하는지 여부
+    pub fn needs_ocr(&self) -> bool {
+        self.text.chars().filter(|c| !c.is_whitespace()).count() < MIN_PAGE_TEXT_LENGTH
+    }
+
+    /// OCR한 글자를 페이지 끝에 `SpanKind::Ocr`로 덧붙이는 함수
+    pub fn push_ocr(&mut self, text: &str) {
+        self.push_span(text, SpanKind::Ocr);
+    }
+
+    /// 표를 페이지 끝에 Markdown 표(`SpanKind::Table`)로 덧붙이는 함수
+    pub fn push_table(&mut self, table: Table) {
+        self.push_span(&table.to_markdown(), SpanKind::Table);
+        self.tables.push(table);
+    }
+
+    /// 글자를 줄을 바꾸어 페이지 끝에 `kind` 부분으로 덧붙이는 함수
+    fn push_span(&mut self, text: &str, kind: SpanKind) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        let start = self.text.chars().count();
+        self.text.push_str(text);
+        if let Some(last) = self.spans.last_mut() {
+            // 줄바꿈은 앞 부분에 포함하여 부분들이 빈틈없이 이어지게 함
+            last.end = start;
+        }
+        self.spans.push(Span {
+            start,
+            end: start + text.chars().count(),
+            kind,
+        });
+    }
+
+    /// 부분별 (글자, 종류, 페이지에서의 시작 위치) 목록
+    pub fn span_texts(&self) -> Vec<(String, SpanKind, usize)> {
+        let chars = self.text.chars().collect::<Vec<_>>();
+        self.spans
+            .iter()
+            .map(|span| {
+                (
+                    chars[span.start..span.end].iter().collect(),
+                    span.kind,
+                    span.start,
+                )
+            })
+            .collect()
+    }
+}
+
+/// PDF에서 추출한 페이지별 글자와 정보
+#[derive(Debug, Clone, Default)]
+pub struct ExtractedDocument {
+    /// 글자를 추출한 백엔드 이름 (`PdfExtractor::name`)
+    #[allow(unused)]
+    pub backend: &'static str,
+    /// PDF의 전체 페이지 수 (글자를 추출하지 못한 페이지 포함)
+    pub page_count: usize,
+    #[allow(unused)]
+    pub title: Option<String>,
+    pub pages: Vec<Page>,
+}
+
+impl ExtractedDocument {
+    /// 공백이 아닌 글자가 하나도 없는지 여부 (스캔한 PDF 등)
+    pub fn is_empty(&self) -> bool {
+        self.pages.iter().all(Page::is_empty)
+    }
+}
+
+#[derive(Debug)]
+pub enum PdfError {
+    /// (Backend, Message)
+    Backend(&'static str, String),
+    /// (Backend, Panic Message)
+    Panicked(&'static str, String),
+    /// 모든 백엔드가 실패한 경우 각 백엔드의 에러
+    AllFailed(Vec<PdfError>),
+}
+
+impl std::fmt::Display for PdfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Backend(backend, message) => write!(f, "{backend}: {message}"),
+            Self::Panicked(backend, message) => write!(f, "{backend}: panicked: {message}"),
+            Self::AllFailed(errors) => {
+                write!(f, "all pdf backends failed")?;
+                for e in errors {
+                    write!(f, "\n  {e}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PdfError {}
+
+/// PDF에서 페이지별 글자를 추출하는 백엔드
+pub trait PdfExtractor {
+    /// 에러와 `ExtractedDocument::backend`에 표시할 이름
+    fn name(&self) -> &'static str;
+
+    fn extract(&self, path: &Path) -> Result<ExtractedDocument, PdfError>;
+}
+
+/// `pdf-extract` 백엔드
+///
+/// 글꼴의 문자 코드표를 해석하므로 글자가 가장 정확하지만, 일부 PDF에서 panic이 발생한다.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PdfExtractBackend;
+
+impl PdfExtractor for PdfExtractBackend {
+    fn name(&self) -> &'static str {
+        "pdf-extract"
+    }
+
+    fn extract(&self, path: &Path) -> Result<ExtractedDocument, PdfError> {
+        let pages = pdf_extract::extract_text_by_pages(path)
+            .map_err(|e| PdfError::Backend(self.name(), e.to_string()))?;
+
+        Ok(ExtractedDocument {
+            backend: self.name(),
+            page_count: pages.len(),
+            title: None,
+            pages: pages
+                .into_iter()
+                .zip(1..)
+                .map(|(text, number)| Page::new(number, text))
+                .collect(),
+        })
+    }
+}
+
+/// `pdf` (pdf-rs) 백엔드
+///
+/// 내용 스트림의 글자 출력 명령을 위치에 따라 읽는 순서로 배치한다 (`layout::reading_order`).
+/// 괘선과 글자 정렬로 찾은 표는 본문에서 빼고 Markdown 표로 덧붙인다.
+/// 글자 코드를 글꼴의 문자 코드표 없이 그대로 읽으므로, 다른 백엔드가 실패한 경우에만 사용한다.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PdfRsBackend;
+
+impl PdfExtractor for PdfRsBackend {
+    fn name(&self) -> &'static str {
+        "pdf-rs"
+    }
+
+    fn extract(&self, path: &Path) -> Result<ExtractedDocument, PdfError> {
+        let error = |e: pdf::PdfError| PdfError::Backend(self.name(), e.to_string());
+        let file = FileOptions::cached().open(path).map_err(error)?;
+        let resolver = file.resolver();
+
+        let mut pages = vec![];
+        for (page, number) in file.pages().zip(1..) {
+            // 읽을 수 없는 페이지는 건너뛰고 나머지 페이지를 추출
+            let Ok(page) = page else {
+                continue;
+            };
+            let Some(contents) = page.contents.as_ref() else {
+                continue;
+            };
+            let Ok(operations) = contents.operations(&resolver) else {
+                continue;
+            };
+            let (runs, rulings) = ContentState::default().read(&operations);
+            let (tables, runs) = table::detect_tables(runs, &rulings);
+            let mut page = Page::new(number, layout::reading_order(runs));
+            for table in tables {
+                page.push_table(table);
+            }
+            pages.push(page);
+        }
+
+        Ok(ExtractedDocument {
+            backend: self.name(),
+            page_count: file.num_pages() as usize,
+            title: None,
+            pages,
+        })
+    }
 }
 
-pub fn read_pdf_by_pdf_rs(file_path: &std::path::Path) -> Result<String, String> {
+/// `lopdf` 백엔드
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LopdfBackend;
+
+impl PdfExtractor for LopdfBackend {
+    fn name(&self) -> &'static str {
+        "lopdf"
+    }
+
+    fn extract(&self, path: &Path) -> Result<ExtractedDocument, PdfError> {
+        let error = |e: lopdf::Error| PdfError::Backend(self.name(), e.to_string());
+        let doc = lopdf::Document::load(path).map_err(error)?;
+
+        let page_numbers = doc.get_pages().into_keys().collect::<Vec<_>>();
+        let mut pages = Vec::with_capacity(page_numbers.len());
+        for number in page_numbers.iter().copied() {
+            let text = doc.extract_text(&[number]).map_err(error)?;
+            pages.push(Page::new(number, text.replace(" \n", " ")));
+        }
+
+        Ok(ExtractedDocument {
+            backend: self.name(),
+            page_count: page_numbers.len(),
+            title: lopdf_title(&doc),
+            pages,
+        })
+    }
+}
+
+/// 백엔드를 차례로 시도하여 처음으로 글자를 추출한 결과를 사용하는 `PdfExtractor`
+///
+/// 백엔드가 에러를 반환하거나 panic이 발생하거나 글자를 추출하지 못하면 다음 백엔드를 시도한다.
+/// 모든 백엔드가 글자를 추출하지 못했지만 PDF는 읽은 경우(스캔한 PDF 등), 처음으로 읽은 결과를 사용한다.
+pub struct FallbackExtractor {
+    extractors: Vec<Box<dyn PdfExtractor + Send + Sync>>,
+}
+
+impl Default for FallbackExtractor {
+    fn default() -> Self {
+        // `PdfRsBackend`는 글꼴의 `/Encoding`, `/ToUnicode`로 글자를 해석하지 않아 CID 글꼴(한글 PDF 등)의
+        // 글자가 깨지므로, 문자 코드표를 해석하는 백엔드를 먼저 시도하고 표는 `attach_tables`로 찾음
+        Self::new(vec![
+            Box::new(PdfExtractBackend),
+            Box::new(LopdfBackend),
+            Box::new(PdfRsBackend),
+        ])
+    }
+}
+
+impl FallbackExtractor {
+    pub fn new(extractors: Vec<Box<dyn PdfExtractor + Send + Sync>>) -> Self {
+        Self { extractors }
+    }
+}
+
+impl PdfExtractor for FallbackExtractor {
+    fn name(&self) -> &'static str {
+        "fallback"
+    }
+
+    fn extract(&self, path: &Path) -> Result<ExtractedDocument, PdfError> {
+        let mut errors = vec![];
+        let mut empty = None;
+        for extractor in &self.extractors {
+            match catch_unwind(AssertUnwindSafe(|| extractor.extract(path))) {
+                Ok(Ok(document)) if !document.is_empty() => return Ok(document),
+                Ok(Ok(document)) => {
+                    empty.get_or_insert(document);
+                }
+                Ok(Err(e)) => errors.push(e),
+                Err(panic) => errors.push(PdfError::Panicked(
+                    extractor.name(),
+                    panic_message(panic.as_ref()),
+                )),
+            }
+        }
+
+        empty.ok_or(PdfError::AllFailed(errors))
+    }
+}
+
+/// 기본 백엔드 순서(`FallbackExtractor::default`)로 PDF의 글자를 추출하는 함수
+///
+/// 다른 백엔드로 추출한 경우 `attach_tables`로 표를 찾아 덧붙이고, 글자가 적은 페이지(스캔한 PDF 등)는
+/// `ocr_pages`로 이미지의 글자를 보충한다.
+pub fn read_pdf_file(path: &Path) -> Result<ExtractedDocument, PdfError> {
+    let mut document = FallbackExtractor::default().extract(path)?;
+    if document.backend != PdfRsBackend.name() {
+        // 표를 찾지 못하더라도 추출한 글자는 사용할 수 있으므로 무시
+        let _ = attach_tables(path, &mut document);
+    }
+    if document.pages.len() < document.page_count || document.pages.iter().any(Page::needs_ocr) {
+        // OCR에 실패하더라도 추출한 글자는 사용할 수 있으므로 무시
+        let _ = ocr_pages(path, &mut document);
+    }
+
+    Ok(document)
+}
+
+/// 페이지 트리의 `/Count`로 PDF의 페이지 수를 읽는 함수
+///
+/// 교차 참조 표와 문서 카탈로그만 읽으므로 페이지 내용을 해석하지 않는다. (`index --dry-run`)
+pub fn page_count(path: &Path) -> Result<usize, PdfError> {
     let file = FileOptions::cached()
-        .open(file_path)
-        .map_err(|e| e.to_string())?;
+        .open(path)
+        .map_err(|e| PdfError::Backend("page count", e.to_string()))?;
+    Ok(file.num_pages() as usize)
+}
+
+/// 내용 스트림의 괘선과 글자 정렬로 찾은 표를 페이지에 덧붙이고, 찾은 표 수를 반환하는 함수
+///
+/// `PdfRsBackend`가 아닌 백엔드의 페이지 글자에는 표의 글자가 이미 섞여 있지만, 문서 조각으로 나눌 때
+/// 행이 유지되도록 표를 따로 덧붙인다.
+pub fn attach_tables(path: &Path, document: &mut ExtractedDocument) -> Result<usize, PdfError> {
+    let error = |e: pdf::PdfError| PdfError::Backend("table", e.to_string());
+    let file = FileOptions::cached().open(path).map_err(error)?;
     let resolver = file.resolver();
-    let mut images: Vec<_> = vec![];
-    for page in file.pages() {
-        if page.is_err() {
+
+    let mut count = 0;
+    for (page, number) in file.pages().zip(1..) {
+        let Ok(page) = page else {
+            continue;
+        };
+        let Ok(index) = document
+            .pages
+            .binary_search_by_key(&number, |page| page.number)
+        else {
             continue;
+        };
+        let Some(Ok(operations)) = page
+            .contents
+            .as_ref()
+            .map(|contents| contents.operations(&resolver))
+        else {
+            continue;
+        };
+
+        let (runs, rulings) = ContentState::default().read(&operations);
+        let (tables, _) = table::detect_tables(runs, &rulings);
+        count += tables.len();
+        for table in tables {
+            document.pages[index].push_table(table);
         }
-        let page = page.unwrap();
-        let resources = page.resources().unwrap();
-        images.extend(
-            resources
-                .xobjects
-                .iter()
-                .map(|(_name, &r)| resolver.get(r).unwrap())
-                .filter(|o| matches!(**o, pdf::object::XObject::Image(_))),
-        );
-    }
-
-    let mut result = String::new();
-    for (i, o) in images.iter().enumerate() {
-        let img = match **o {
-            pdf::object::XObject::Image(ref im) => im,
-            _ => continue,
+    }
+
+    Ok(count)
+}
+
+/// 글자가 적은 페이지에 포함된 이미지를 OCR하여 페이지 글자에 덧붙이는 함수
+///
+/// 백엔드가 건너뛴 페이지도 OCR하여 추가하며, OCR한 페이지 수를 반환한다.
+pub fn ocr_pages(path: &Path, document: &mut ExtractedDocument) -> Result<usize, PdfError> {
+    let error = |e: pdf::PdfError| PdfError::Backend("ocr", e.to_string());
+    let file = FileOptions::cached().open(path).map_err(error)?;
+    let resolver = file.resolver();
+
+    let mut count = 0;
+    for (page, number) in file.pages().zip(1..) {
+        let Ok(page) = page else {
+            continue;
         };
-        let (mut data, filter) = img
-            .raw_image_data(&resolver)
-            .expect("Failed to get image data");
-        let ext = match filter {
-            Some(StreamFilter::DCTDecode(_)) => "jpeg",
-            Some(StreamFilter::JBIG2Decode(_)) => "jbig2",
-            Some(StreamFilter::JPXDecode) => "jp2k",
-            Some(StreamFilter::FlateDecode(_)) => "png",
-            Some(StreamFilter::CCITTFaxDecode(_)) => {
-                data = fax::tiff::wrap(&data, img.width, img.height).into();
-                "tiff"
+        let index = match document
+            .pages
+            .binary_search_by_key(&number, |page| page.number)
+        {
+            Ok(index) if !document.pages[index].needs_ocr() => continue,
+            Ok(index) => index,
+            Err(index) => {
+                document
+                    .pages
+                    .insert(index, Page::new(number, String::new()));
+                index
             }
-            _ => continue,
         };
 
-        // let fname = format!("./images/{}_extracted_image_{}.{}", name, i, ext);
-        // std::fs::write(fname.as_str(), data.clone()).unwrap();
+        let Ok(images) = page_images(&page, &resolver) else {
+            continue;
+        };
+        let text = images
+            .iter()
+            .filter_map(|image| match image {
+                Ok(PageImage::Encoded(data)) => Some(crate::ocr::ocr_by_buffer(data)),
+                Ok(PageImage::Decoded(image)) => Some(crate::ocr::ocr_image(image)),
+                Err(reason) => {
+                    eprintln!(
+                        "Skipping image on page {number} of {}: {reason}",
+                        path.display()
+                    );
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !text.trim().is_empty() {
+            document.pages[index].push_ocr(&text);
+            count += 1;
+        }
     }
 
-    println!("File pages {:?}", file.num_pages());
-    file.pages().for_each(|page| {
-        if page.is_err() {
-            println!("Error: {:?}", page.err());
-            return;
+    Ok(count)
+}
+
+/// 변환 행렬 `[a b 0; c d 0; e f 1]` (PDF와 같이 행 벡터에 곱함)
+#[derive(Debug, Clone, Copy)]
+struct Transform {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    e: f32,
+    f: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::translate(0.0, 0.0)
+    }
+}
+
+impl From<Matrix> for Transform {
+    fn from(m: Matrix) -> Self {
+        let Matrix { a, b, c, d, e, f } = m;
+        Self { a, b, c, d, e, f }
+    }
+}
+
+impl Transform {
+    fn translate(x: f32, y: f32) -> Self {
+        Self {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: x,
+            f: y,
         }
-        let page = page.unwrap();
-        let contents = page.contents.as_ref().unwrap();
-        let op = contents.operations(&resolver).unwrap();
-        let content: String = op
-            .iter()
-            .map(|o| match o {
+    }
+
+    /// `self`를 적용한 후 `next`를 적용하는 변환 (`self × next`)
+    fn then(self, next: Self) -> Self {
+        Self {
+            a: self.a * next.a + self.b * next.c,
+            b: self.a * next.b + self.b * next.d,
+            c: self.c * next.a + self.d * next.c,
+            d: self.c * next.b + self.d * next.d,
+            e: self.e * next.a + self.f * next.c + next.e,
+            f: self.e * next.b + self.f * next.d + next.f,
+        }
+    }
+
+    /// 가로 방향 크기 배율
+    fn horizontal_scale(self) -> f32 {
+        self.a.hypot(self.b)
+    }
+
+    /// 세로 방향 크기 배율
+    fn vertical_scale(self) -> f32 {
+        self.c.hypot(self.d)
+    }
+
+    /// 점 `(x, y)`를 변환하는 함수
+    fn apply(self, x: f32, y: f32) -> (f32, f32) {
+        (
+            x * self.a + y * self.c + self.e,
+            x * self.b + y * self.d + self.f,
+        )
+    }
+}
+
+/// `q`/`Q`로 저장하고 되돌리는 그래픽 상태 중 글자 위치에 필요한 값
+#[derive(Debug, Clone, Copy)]
+struct GraphicsState {
+    ctm: Transform,
+    font_size: f32,
+    leading: f32,
+    char_spacing: f32,
+    word_spacing: f32,
+    /// 가로 배율 (`Tz`는 백분율)
+    horizontal_scaling: f32,
+    rise: f32,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        Self {
+            ctm: Transform::default(),
+            font_size: 0.0,
+            leading: 0.0,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            horizontal_scaling: 1.0,
+            rise: 0.0,
+        }
+    }
+}
+
+/// 내용 스트림을 해석하여 글자열과 괘선의 페이지 좌표를 계산하는 상태
+///
+/// 글꼴의 글자 폭은 읽지 않으므로 `GLYPH_WIDTH`로 추정한다.
+#[derive(Debug, Default)]
+struct ContentState {
+    state: GraphicsState,
+    stack: Vec<GraphicsState>,
+    /// 글자 행렬 (`Tm`)
+    text_matrix: Transform,
+    /// 줄 시작 행렬 (`Tlm`)
+    line_matrix: Transform,
+    /// 그리는 중인 경로의 수평선과 수직선
+    path: Vec<Ruling>,
+    /// 경로의 시작점과 현재 점 (페이지 좌표)
+    path_start: (f32, f32),
+    path_current: (f32, f32),
+}
+
+impl ContentState {
+    /// 내용 스트림에서 출력하는 (글자열들, 선을 긋거나 칠한 괘선들)을 계산하는 함수
+    fn read(mut self, operations: &[Op]) -> (Vec<TextRun>, Vec<Ruling>) {
+        let mut runs = vec![];
+        let mut rulings = vec![];
+        for operation in operations {
+            match operation {
+                Op::MoveTo { p } => {
+                    self.path_start = self.state.ctm.apply(p.x, p.y);
+                    self.path_current = self.path_start;
+                }
+                Op::LineTo { p } => {
+                    let point = self.state.ctm.apply(p.x, p.y);
+                    self.path.extend(Ruling::new(self.path_current, point));
+                    self.path_current = point;
+                }
+                Op::Close => {
+                    self.path
+                        .extend(Ruling::new(self.path_current, self.path_start));
+                    self.path_current = self.path_start;
+                }
+                Op::Rect { rect } => {
+                    let corners = [
+                        (rect.x, rect.y),
+                        (rect.x + rect.width, rect.y),
+                        (rect.x + rect.width, rect.y + rect.height),
+                        (rect.x, rect.y + rect.height),
+                    ]
+                    .map(|(x, y)| self.state.ctm.apply(x, y));
+                    // 얇은 사각형은 긴 변만 괘선이 됨
+                    for i in 0..corners.len() {
+                        self.path
+                            .extend(Ruling::new(corners[i], corners[(i + 1) % corners.len()]));
+                    }
+                    self.path_start = corners[0];
+                    self.path_current = corners[0];
+                }
+                Op::Stroke | Op::Fill { .. } | Op::FillAndStroke { .. } => {
+                    rulings.append(&mut self.path)
+                }
+                Op::EndPath => self.path.clear(),
+                Op::Save => self.stack.push(self.state),
+                Op::Restore => self.state = self.stack.pop().unwrap_or_default(),
+                Op::Transform { matrix } => {
+                    self.state.ctm = Transform::from(*matrix).then(self.state.ctm)
+                }
+                Op::BeginText => self.set_text_matrix(Transform::default()),
+                Op::SetTextMatrix { matrix } => self.set_text_matrix((*matrix).into()),
+                Op::MoveTextPosition { translation } => self.set_text_matrix(
+                    Transform::translate(translation.x, translation.y).then(self.line_matrix),
+                ),
+                Op::TextNewline => self.set_text_matrix(
+                    Transform::translate(0.0, -self.state.leading).then(self.line_matrix),
+                ),
+                Op::Leading { leading } => self.state.leading = *leading,
+                Op::TextFont { size, .. } => self.state.font_size = *size,
+                Op::CharSpacing { char_space } => self.state.char_spacing = *char_space,
+                Op::WordSpacing { word_space } => self.state.word_spacing = *word_space,
+                Op::TextScaling { horiz_scale } => {
+                    self.state.horizontal_scaling = horiz_scale / 100.0
+                }
+                Op::TextRise { rise } => self.state.rise = *rise,
                 Op::TextDraw { text } => {
-                    println!("Text: {:?}", text);
-                    text.to_string_lossy()
+                    runs.extend(self.draw(&[TextDrawAdjusted::Text(text.clone())]))
                 }
-                Op::TextDrawAdjusted { array } => array
-                    .iter()
-                    .map(|p| p.to_string())
-                    .collect::<Vec<String>>()
-                    .join(" "),
-                _ => String::new(),
-            })
-            .collect();
+                Op::TextDrawAdjusted { array } => runs.extend(self.draw(array)),
+                _ => {}
+            }
+        }
+
+        (runs, rulings)
+    }
+
+    fn set_text_matrix(&mut self, matrix: Transform) {
+        self.text_matrix = matrix;
+        self.line_matrix = matrix;
+    }
+
+    /// 글자 출력 명령 하나(`Tj`, `TJ`)를 글자열 하나로 만들고 글자 행렬을 이동하는 함수
+    ///
+    /// 간격 조정값(kerning)은 위치만 이동하고 글자로 넣지 않으며, 넓은 간격만 띄어쓰기로 바꾼다.
+    fn draw(&mut self, array: &[TextDrawAdjusted]) -> Option<TextRun> {
+        let state = self.state;
+        let font = Transform {
+            a: state.font_size * state.horizontal_scaling,
+            b: 0.0,
+            c: 0.0,
+            d: state.font_size,
+            e: 0.0,
+            f: state.rise,
+        };
+        let start = font.then(self.text_matrix).then(state.ctm);
+        let scale = self.text_matrix.then(state.ctm).horizontal_scale();
+
+        let mut text = String::new();
+        let mut advance = 0.0;
+        for item in array {
+            match item {
+                TextDrawAdjusted::Text(string) => {
+                    for c in string.to_string_lossy().chars() {
+                        let spacing = if c == ' ' { state.word_spacing } else { 0.0 };
+                        advance +=
+                            (glyph_width(c) * state.font_size + state.char_spacing + spacing)
+                                * state.horizontal_scaling;
+                        text.push(c);
+                    }
+                }
+                TextDrawAdjusted::Spacing(adjustment) => {
+                    let shift = -adjustment / 1000.0;
+                    if shift >= ADJUSTMENT_SPACE && !text.ends_with(' ') {
+                        text.push(' ');
+                    }
+                    advance += shift * state.font_size * state.horizontal_scaling;
+                }
+            }
+        }
+        self.text_matrix = Transform::translate(advance, 0.0).then(self.text_matrix);
+
+        Some(TextRun {
+            x: start.e,
+            y: start.f,
+            // 간격 조정값으로 뒤로 이동한 경우에도 폭은 0 이상
+            width: (advance * scale).max(0.0),
+            size: start.vertical_scale(),
+            text,
+        })
+        .filter(|run| !run.text.trim().is_empty())
+    }
+}
 
-        // println!("Contents: {:?}", content);
-    });
+/// 글자 크기 대비 추정 글자 폭
+fn glyph_width(c: char) -> f32 {
+    match c {
+        '\u{1100}'..='\u{11ff}'
+        | '\u{2e80}'..='\u{9fff}'
+        | '\u{ac00}'..='\u{d7af}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{ff00}'..='\u{ff60}' => GLYPH_WIDTH * 2.0,
+        _ => GLYPH_WIDTH,
+    }
+}
 
-    Ok(result)
+/// PDF 정보 사전(`/Info`)의 제목
+fn lopdf_title(doc: &lopdf::Document) -> Option<String> {
+    let info = doc.trailer.get(b"Info").ok()?;
+    let (_, info) = doc.dereference(info).ok()?;
+    let title = info.as_dict().ok()?.get(b"Title").ok()?;
+    lopdf::decode_text_string(title)
+        .ok()
+        .filter(|title| !title.trim().is_empty())
 }
 
-pub fn read_pdf_by_lopdf(file_path: &std::path::Path) -> Result<String, String> {
-    let doc = lopdf::Document::load(file_path).map_err(|e| e.to_string())?;
+/// `catch_unwind`로 잡은 panic의 메시지
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| String::from("unknown panic"))
+}
 
-    for (page_number, page_id) in doc.get_pages() {
-        let page = doc
-            .extract_text(&[page_number])
-            .map_err(|op| op.to_string())?;
-        let page = page.replace(" \n", " ");
-        std::fs::write(format!("./assets/page_{}.txt", page_number), page).unwrap();
+/// OCR할 수 있도록 추출한 페이지 이미지
+enum PageImage {
+    /// 형식을 데이터로 판별할 수 있는 이미지 파일 데이터 (JPEG, TIFF)
+    Encoded(Vec<u8>),
+    /// 압축을 풀어 샘플로 만든 이미지
+    Decoded(DynamicImage),
+}
+
+/// 페이지의 이미지 `XObject`를 추출하는 함수
+///
+/// JPEG는 그대로 사용하고, CCITT 팩스 이미지는 TIFF로 감싸며, Flate 이미지는 압축을 풀어 샘플로 만든다.
+/// OCR할 수 없는 이미지(JBIG2, JPEG 2000 등)는 건너뛴 이유를 `Err`로 반환한다.
+fn page_images(
+    page: &pdf::object::Page,
+    resolver: &impl Resolve,
+) -> Result<Vec<Result<PageImage, String>>, pdf::PdfError> {
+    let resources = page.resources()?;
+    let mut images = vec![];
+    for &r in resources.xobjects.values() {
+        let Ok(object) = resolver.get(r) else {
+            continue;
+        };
+        let XObject::Image(ref img) = *object else {
+            continue;
+        };
+        let (data, filter) = match img.raw_image_data(resolver) {
+            Ok(image) => image,
+            Err(e) => {
+                images.push(Err(format!("failed to read image data: {e}")));
+                continue;
+            }
+        };
+        let image = match filter {
+            Some(StreamFilter::DCTDecode(_)) => Ok(PageImage::Encoded(data.to_vec())),
+            Some(StreamFilter::CCITTFaxDecode(_)) => Ok(PageImage::Encoded(fax::tiff::wrap(
+                &data, img.width, img.height,
+            ))),
+            Some(filter @ StreamFilter::FlateDecode(_)) => pdf::enc::decode(&data, filter)
+                .map_err(|e| format!("failed to decode Flate image: {e}"))
+                .and_then(|samples| decode_samples(&samples, img))
+                .map(PageImage::Decoded),
+            // 압축 없이 저장된 샘플
+            None => decode_samples(&data, img).map(PageImage::Decoded),
+            Some(StreamFilter::JBIG2Decode(_)) => {
+                Err(String::from("JBIG2 images are not supported"))
+            }
+            Some(StreamFilter::JPXDecode) => {
+                Err(String::from("JPEG 2000 images are not supported"))
+            }
+            Some(filter) => Err(format!("unsupported image filter {filter:?}")),
+        };
+        images.push(image);
+    }
+
+    Ok(images)
+}
+
+/// 압축을 푼 이미지 샘플을 `width`, `height`, `bits_per_component`로 그레이스케일 또는 RGB 이미지로 만드는 함수
+///
+/// 색 공간이 없거나 알 수 없으면 데이터 길이로 색 성분 수(1, 3, 4)를 추정하며, CMYK는 RGB로 바꾼다.
+fn decode_samples(data: &[u8], img: &ImageDict) -> Result<DynamicImage, String> {
+    let (width, height) = (img.width as usize, img.height as usize);
+    // 마스크 이미지는 항상 1비트
+    let bits = if img.image_mask {
+        1
+    } else {
+        img.bits_per_component.unwrap_or(8)
+    };
+    if ![1, 2, 4, 8, 16].contains(&bits) {
+        return Err(format!("unsupported bits per component {bits}"));
+    }
+    let bits = bits as usize;
+    let row_length = |components: usize| (width * components * bits).div_ceil(8);
+    let components = match img.color_space {
+        Some(ColorSpace::DeviceGray) => 1,
+        Some(ColorSpace::DeviceRGB) => 3,
+        Some(ColorSpace::DeviceCMYK) => 4,
+        Some(ColorSpace::Indexed(..)) => {
+            return Err(String::from("indexed color images are not supported"))
+        }
+        _ if img.image_mask => 1,
+        _ => [1, 3, 4]
+            .into_iter()
+            .find(|&components| row_length(components) * height == data.len())
+            .ok_or_else(|| String::from("unknown image color space"))?,
+    };
+    let row_length = row_length(components);
+    if width == 0 || height == 0 || data.len() < row_length * height {
+        return Err(String::from("truncated image data"));
     }
 
-    Ok(String::new())
+    // 각 샘플을 8비트로 바꿈 (16비트는 상위 바이트만 사용)
+    let max = (1u32 << bits.min(8)) - 1;
+    let samples = data
+        .chunks_exact(row_length)
+        .take(height)
+        .flat_map(|row| {
+            (0..width * components).map(move |i| match bits {
+                8 => row[i],
+                16 => row[i * 2],
+                _ => {
+                    let bit = i * bits;
+                    let value = (row[bit / 8] >> (8 - bits - bit % 8)) as u32 & max;
+                    (value * 255 / max) as u8
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let (width, height) = (img.width, img.height);
+    let image = match components {
+        1 => GrayImage::from_raw(width, height, samples).map(DynamicImage::ImageLuma8),
+        3 => RgbImage::from_raw(width, height, samples).map(DynamicImage::ImageRgb8),
+        _ => {
+            let samples = samples
+                .chunks_exact(4)
+                .flat_map(|cmyk| {
+                    let k = 255 - cmyk[3] as u32;
+                    cmyk[..3]
+                        .iter()
+                        .map(move |&c| ((255 - c as u32) * k / 255) as u8)
+                })
+                .collect();
+            RgbImage::from_raw(width, height, samples).map(DynamicImage::ImageRgb8)
+        }
+    };
+
+    image.ok_or_else(|| String::from("truncated image data"))
 }
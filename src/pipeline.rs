@@ -0,0 +1,784 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use pyo3::Python;
+use tokio::{
+    sync::{mpsc, Semaphore},
+    task::JoinSet,
+};
+
+use crate::{
+    config::Config,
+    discovery::{DiscoveryPages, DiscoverySource},
+    embed::{self, format_py_error, DocumentLoader, Embedder, FileChunks, Models, LOADER_VERSION},
+    error_report,
+    loader::{self, Loader},
+    manifest::{FileState, Manifest, ManifestSnapshot},
+    native::{CancelToken, FileHit},
+    preview::{PlannedFile, Preview},
+    progress::{Progress, ProgressEvent},
+    report::{FileReport, FileStatus, IngestReport},
+};
+
+/// 이 개수만큼 파일을 `Vector Store`에 저장할 때마다 `Vector Store`와 `Manifest`를 저장 (`flush`)
+///
+/// 중간에 종료되어도 다음 실행에서 저장된 파일은 건너뛴다.
+const CHECKPOINT_INTERVAL: usize = 16;
+
+pub type PipelineError = Box<dyn Error + Send + Sync>;
+
+/// 진행 상황을 받는 콜백 (record 단계에서 호출됨)
+pub type ProgressCallback<'a> = &'a mut (dyn FnMut(&ProgressEvent<'_>, &Progress) + Send);
+
+/// discover 단계가 다른 단계에 알리는 검색 상태
+#[derive(Debug, Default)]
+struct DiscoveryState {
+    /// 지금까지 전달한 파일 수
+    discovered: AtomicUsize,
+    done: AtomicBool,
+}
+
+/// `check` 단계에서 상태를 확인한 파일
+struct CheckedFile {
+    hit: FileHit,
+    state: std::io::Result<FileState>,
+}
+
+/// embed 단계에서 처리할 파일
+enum StoreJob {
+    /// 문서 조각을 임베딩하여 기존 문서 대신 저장
+    Index {
+        hit: FileHit,
+        hash: String,
+        loader: Loader,
+        chunks: FileChunks,
+        started: Instant,
+    },
+    /// 이미 저장된 파일이 지원하지 않는 형식으로 바뀐 경우 기존 문서를 삭제
+    Unsupported { hit: FileHit, started: Instant },
+}
+
+impl StoreJob {
+    /// 임베딩할 문서 조각 수
+    fn len(&self) -> usize {
+        match self {
+            Self::Index { chunks, .. } => chunks.len,
+            Self::Unsupported { .. } => 0,
+        }
+    }
+}
+
+/// 파일 처리 결과에 따라 `Manifest`에 반영할 내용
+enum ManifestUpdate {
+    /// 새로 저장한 문서 조각을 기록
+    Insert {
+        hash: String,
+        chunk_ids: Vec<String>,
+    },
+    /// 기존 문서를 삭제했으므로 기록도 삭제
+    Remove,
+    /// 기존 문서가 그대로 있으므로 기록도 그대로 둠 (다음 실행에서 다시 시도)
+    Keep,
+}
+
+/// extract, embed 단계가 record 단계로 보내는 처리 결과
+enum Processed {
+    /// 바뀐 파일의 처리를 시작함
+    Started(PathBuf),
+    /// 바뀌지 않았거나 읽을 수 없어 건너뛴 파일
+    Skipped {
+        hit: FileHit,
+        file: FileReport,
+        /// 내용은 같고 수정 시각만 바뀌어 `Manifest`의 수정 시각을 갱신해야 하는지 여부
+        touched: bool,
+    },
+    /// 처리를 마친 파일
+    Finished {
+        hit: FileHit,
+        file: FileReport,
+        update: ManifestUpdate,
+    },
+    /// `Vector Store`를 파일에 저장함 (이전에 받은 결과를 `Manifest`에 저장해도 됨)
+    Flushed,
+}
+
+/// 파일 검색부터 `Vector Store` 저장까지의 단계를 동시에 실행하는 함수
+///
+/// discover → check → extract → embed → record
+/// - discover: 별도 스레드에서 페이지 단위로 파일을 검색한다. 검색에 실패하면 한 번만 다른 소스로 다시 검색한다.
+/// - check: tokio에서 최대 `check_workers`개의 파일 해시를 동시에 계산하여 `manifest`와 비교한다.
+/// - extract: `extract_workers`개의 스레드에서 바뀐 파일의 문서를 추출하여 문서 조각으로 나눈다.
+///   `pdf_reader` 등 Rust 추출은 GIL을 놓고 실행하므로 다른 파일의 임베딩과 겹쳐서 실행된다.
+/// - embed: 전용 스레드 하나에서 준비된 여러 파일의 문서 조각을 `embed_batch_size`개까지 모아 한 번에
+///   임베딩하고, 기존 문서를 삭제한 후 저장한다. `CHECKPOINT_INTERVAL`개마다 `Vector Store`를 저장한다.
+/// - record: 호출한 스레드에서 처리 결과를 `manifest`와 `IngestReport`에 기록하고 진행 상황을 전달한다.
+///
+/// 단계 사이는 크기가 `queue_size`인 채널로 연결되어, 뒤 단계가 밀리면 앞 단계도 기다린다.
+/// GIL이 필요한 단계는 extract, embed뿐이며, 실행하는 동안 호출한 스레드의 GIL을 놓으므로
+/// tokio 런타임 안에서 호출해야 한다. 파일을 처리할 때마다 `on_progress`로 진행 상황을 전달한다.
+///
+/// `cancel`이 취소되면 임베딩 중인 파일까지만 저장하고 `IngestReport::cancelled`를 설정하여 반환한다.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    py: Python<'_>,
+    config: &Config,
+    models: &Models,
+    manifest: &mut Manifest,
+    discover: &(impl Fn(DiscoverySource) -> DiscoveryPages + Sync),
+    source: DiscoverySource,
+    cancel: &CancelToken,
+    on_progress: ProgressCallback<'_>,
+) -> Result<IngestReport, PipelineError> {
+    let queue_size = config.pipeline.queue_size.max(1);
+    let check_workers = config.pipeline.check_workers.max(1);
+    let extract_workers = config.pipeline.extract_workers.max(1);
+    let batch_size = config.pipeline.embed_batch_size.max(1);
+    let snapshot = manifest.snapshot();
+    let runtime = tokio::runtime::Handle::current();
+    let discovery = DiscoveryState::default();
+
+    let mut report = py.allow_threads(|| {
+        let (hit_tx, hit_rx) = mpsc::channel(queue_size);
+        let (checked_tx, checked_rx) = mpsc::channel(queue_size);
+        let (job_tx, job_rx) = mpsc::channel(queue_size);
+        let (processed_tx, processed_rx) = mpsc::channel(queue_size);
+        runtime.spawn(check_files(hit_rx, checked_tx, snapshot, check_workers));
+        let checked_rx = Mutex::new(checked_rx);
+        let loading = Mutex::new(());
+
+        std::thread::scope(|scope| -> Result<IngestReport, PipelineError> {
+            let (discovery, checked_rx, loading) = (&discovery, &checked_rx, &loading);
+            scope.spawn(move || {
+                discover_files(discover, source, cancel, &discovery.discovered, hit_tx);
+                discovery.done.store(true, Ordering::Relaxed);
+            });
+            let extractors = (0..extract_workers)
+                .map(|_| {
+                    let (jobs, processed) = (job_tx.clone(), processed_tx.clone());
+                    scope.spawn(move || {
+                        extract_files(config, models, checked_rx, loading, jobs, processed, cancel)
+                    })
+                })
+                .collect::<Vec<_>>();
+            drop(job_tx);
+            let embedder =
+                scope.spawn(move || embed_files(models, job_rx, batch_size, processed_tx, cancel));
+
+            let report = record_files(manifest, processed_rx, discovery, cancel, on_progress);
+            embedder.join().expect("Failed to join embed thread")?;
+            for extractor in extractors {
+                extractor.join().expect("Failed to join extract thread")?;
+            }
+            report
+        })
+    })?;
+    // embed 단계가 `Vector Store`를 모두 저장했으므로 나머지 기록도 저장
+    manifest.save()?;
+    report.cancelled = cancel.is_cancelled();
+
+    Ok(report)
+}
+
+/// 모델을 불러오지 않고 discover, check 단계만 실행하여 파일마다 적용될 작업을 확인하는 함수 (`index --dry-run`)
+///
+/// `run`과 같은 조건으로 파일을 검색하고 `manifest`와 비교하며, `manifest`는 바꾸지 않는다.
+pub fn preview(
+    config: &Config,
+    manifest: &Manifest,
+    discover: &(impl Fn(DiscoverySource) -> DiscoveryPages + Sync),
+    source: DiscoverySource,
+    cancel: &CancelToken,
+) -> Preview {
+    let queue_size = config.pipeline.queue_size.max(1);
+    let check_workers = config.pipeline.check_workers.max(1);
+    let (hit_tx, hit_rx) = mpsc::channel(queue_size);
+    let (checked_tx, mut checked_rx) = mpsc::channel(queue_size);
+    tokio::runtime::Handle::current().spawn(check_files(
+        hit_rx,
+        checked_tx,
+        manifest.snapshot(),
+        check_workers,
+    ));
+
+    let discovered = AtomicUsize::new(0);
+    let mut preview = std::thread::scope(|scope| {
+        scope.spawn(|| discover_files(discover, source, cancel, &discovered, hit_tx));
+        scope
+            .spawn(|| {
+                let mut preview = Preview::default();
+                while let Some(CheckedFile { hit, state }) = checked_rx.blocking_recv() {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+                    preview.push(PlannedFile::new(manifest, &hit, state));
+                }
+                preview
+            })
+            .join()
+            .expect("Failed to join preview thread")
+    });
+
+    preview.cancelled = cancel.is_cancelled();
+    if !preview.cancelled {
+        for path in manifest.missing() {
+            preview.push(PlannedFile::removed(manifest, path));
+        }
+    }
+
+    preview
+}
+
+/// discover 단계: 검색된 파일 중 존재하고 아직 전달하지 않은 파일을 `hits`로 보내는 함수
+fn discover_files(
+    discover: &impl Fn(DiscoverySource) -> DiscoveryPages,
+    source: DiscoverySource,
+    cancel: &CancelToken,
+    discovered: &AtomicUsize,
+    hits: mpsc::Sender<FileHit>,
+) {
+    let mut pages = discover(source);
+    let mut fallback_used = false;
+    // 현재 소스에서 결과 없이 오류만 반환된 경우 대신 사용할 소스
+    let mut fallback = None;
+    let mut source_found = false;
+    // 대체 소스로 다시 검색할 때 이미 전달한 파일을 건너뛰기 위한 경로 목록
+    let mut discovered_paths = HashSet::new();
+    loop {
+        let page = match pages.next() {
+            Some(Ok(page)) => page,
+            // 읽을 수 없는 루트 디렉토리나 행 등 일부 결과의 오류는 출력하고 나머지 결과를 계속 가져옴
+            Some(Err(e)) => {
+                eprintln!("Failed to search files: {}", error_report(&e));
+                fallback = fallback.or(e.fallback_source());
+                continue;
+            }
+            None => {
+                // 사용자가 검색을 취소한 경우에는 다른 소스로 다시 검색하지 않음
+                let fallback = fallback
+                    .take()
+                    .filter(|_| !source_found && !fallback_used && !cancel.is_cancelled());
+                match fallback {
+                    Some(fallback) => {
+                        eprintln!("Falling back to {fallback:?}...");
+                        fallback_used = true;
+                        pages = discover(fallback);
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+        };
+        source_found |= !page.is_empty();
+
+        for hit in page {
+            if !hit.path.exists() || !discovered_paths.insert(hit.path.clone()) {
+                continue;
+            }
+            if cancel.is_cancelled() {
+                return;
+            }
+            discovered.fetch_add(1, Ordering::Relaxed);
+            // 뒤 단계가 종료된 경우
+            if hits.blocking_send(hit).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// check 단계: 파일 해시를 최대 `workers`개씩 동시에 계산하여 상태를 `checked`로 보내는 함수
+async fn check_files(
+    mut hits: mpsc::Receiver<FileHit>,
+    checked: mpsc::Sender<CheckedFile>,
+    snapshot: ManifestSnapshot,
+    workers: usize,
+) {
+    let semaphore = Arc::new(Semaphore::new(workers));
+    let mut tasks = JoinSet::new();
+    while let Some(mut hit) = hits.recv().await {
+        let Ok(permit) = semaphore.clone().acquire_owned().await else {
+            break;
+        };
+        // 뒤 단계가 종료되면 `hits`를 닫아 discover 단계도 멈추게 함
+        if checked.is_closed() {
+            break;
+        }
+
+        let snapshot = snapshot.clone();
+        let checked = checked.clone();
+        tasks.spawn_blocking(move || {
+            let state = detect_mime(&mut hit).and_then(|_| snapshot.check(&hit, LOADER_VERSION));
+            let _ = checked.blocking_send(CheckedFile { hit, state });
+            drop(permit);
+        });
+        // 완료된 작업 정리
+        while tasks.try_join_next().is_some() {}
+    }
+
+    while tasks.join_next().await.is_some() {}
+}
+
+/// extract 단계: 바뀐 파일의 문서를 추출하여 문서 조각으로 나눈 후 `jobs`로 보내는 함수
+///
+/// 여러 작업자가 `checked`를 나누어 받는다. 바뀌지 않은 파일과 추출에 실패한 파일은 embed 단계를
+/// 거치지 않고 바로 `processed`로 보낸다. 작업을 마치면 `checked`를 닫아 앞 단계도 멈추게 한다.
+fn extract_files(
+    config: &Config,
+    models: &Models,
+    checked: &Mutex<mpsc::Receiver<CheckedFile>>,
+    loading: &Mutex<()>,
+    jobs: mpsc::Sender<StoreJob>,
+    processed: mpsc::Sender<Processed>,
+    cancel: &CancelToken,
+) -> Result<(), PipelineError> {
+    let result = (|| {
+        loop {
+            let received = lock(checked).blocking_recv();
+            let Some(CheckedFile { hit, state }) = received else {
+                return Ok(());
+            };
+            if cancel.is_cancelled() {
+                return Ok(());
+            }
+            // 이미 저장된 파일이면 지원하지 않는 형식으로 바뀌었을 때 기존 문서를 삭제해야 함
+            let stored = matches!(state, Ok(FileState::Modified { .. }));
+            let mut touched = false;
+            let hash = match check_state(&hit, state, || touched = true) {
+                Ok(hash) => hash,
+                Err(file) => {
+                    let skipped = Processed::Skipped { hit, file, touched };
+                    if processed.blocking_send(skipped).is_err() {
+                        return Ok(());
+                    }
+                    continue;
+                }
+            };
+
+            let _ = processed.blocking_send(Processed::Started(hit.path.clone()));
+            let job = match extract_file(config, models, loading, hit, hash, stored)? {
+                Ok(job) => job,
+                Err(finished) => {
+                    let _ = processed.blocking_send(finished);
+                    continue;
+                }
+            };
+            // 뒤 단계가 종료된 경우
+            if jobs.blocking_send(job).is_err() {
+                return Ok(());
+            }
+        }
+    })();
+    lock(checked).close();
+
+    result
+}
+
+/// 파일 하나의 문서를 추출하여 embed 단계의 작업을 만드는 함수
+///
+/// 추출에 실패하면 기존 문서를 그대로 두고 처리 결과를 `Err`로 반환한다.
+/// 지원하지 않는 형식의 파일은 `stored`(이미 저장된 파일)일 때만 기존 문서를 삭제하도록 embed 단계로 보낸다.
+/// 모델을 불러오지 못하면 다른 파일도 처리할 수 없으므로 바깥 `Err`로 반환한다.
+fn extract_file(
+    config: &Config,
+    models: &Models,
+    loading: &Mutex<()>,
+    hit: FileHit,
+    hash: String,
+    stored: bool,
+) -> Result<Result<StoreJob, Processed>, PipelineError> {
+    let started = Instant::now();
+    let Some(loader) = Loader::for_mime(hit.mime) else {
+        eprintln!("Skipping {} ({})", hit.name, hit.mime);
+        if !stored {
+            let file = FileReport {
+                error: Some(format!("unsupported file type {}", hit.mime)),
+                duration: started.elapsed(),
+                ..FileReport::new(hit.path.clone(), FileStatus::Unsupported)
+            };
+            return Ok(Err(Processed::Finished {
+                hit,
+                file,
+                update: ManifestUpdate::Keep,
+            }));
+        }
+        // embed 단계에서 기존 문서를 삭제할 수 있도록 `Vector Store`를 불러옴
+        {
+            let _loading = lock(loading);
+            Python::with_gil(|py| models.embedder(py, config).map(|_| ()))?;
+        }
+        return Ok(Ok(StoreJob::Unsupported { hit, started }));
+    };
+
+    let (document_loader, _) = {
+        // 여러 작업자가 동시에 모델을 불러오지 않도록 GIL을 얻기 전에 잠금
+        let _loading = lock(loading);
+        Python::with_gil(|py| models.ingest(py, std::slice::from_ref(&hit), config))?
+    };
+    eprintln!("Parsing {} ({:?}, {})", hit.name, hit.source, hit.mime);
+    let chunks = Python::with_gil(|py| {
+        document_loader
+            .extract(py, &hit, loader)
+            .map_err(|e| format_py_error(py, &e))
+    });
+
+    Ok(match chunks {
+        Ok(chunks) => Ok(StoreJob::Index {
+            hit,
+            hash,
+            loader,
+            chunks,
+            started,
+        }),
+        Err(e) => {
+            eprintln!("Failed to extract {}: {e}", hit.path.display());
+            let mut file = FileReport::failed(hit.path.clone(), e);
+            file.duration = started.elapsed();
+            Err(Processed::Finished {
+                hit,
+                file,
+                update: ManifestUpdate::Keep,
+            })
+        }
+    })
+}
+
+/// embed 단계: 준비된 파일들의 문서 조각을 `batch_size`개까지 모아 임베딩하고 저장하는 함수
+///
+/// `CHECKPOINT_INTERVAL`개의 파일을 저장할 때마다, 그리고 마지막에 `Vector Store`를 파일에 저장한 후
+/// `Processed::Flushed`를 보낸다. 취소되면 이미 받은 나머지 파일은 저장하지 않는다.
+fn embed_files(
+    models: &Models,
+    mut jobs: mpsc::Receiver<StoreJob>,
+    batch_size: usize,
+    processed: mpsc::Sender<Processed>,
+    cancel: &CancelToken,
+) -> Result<(), PipelineError> {
+    let mut unsaved = 0;
+    while let Some(job) = jobs.blocking_recv() {
+        // 이미 추출된 다른 파일의 문서 조각도 함께 임베딩
+        let mut size = job.len();
+        let mut batch = vec![job];
+        while size < batch_size {
+            let Ok(job) = jobs.try_recv() else {
+                break;
+            };
+            size += job.len();
+            batch.push(job);
+        }
+        if cancel.is_cancelled() {
+            break;
+        }
+
+        // extract 단계가 작업을 보내기 전에 불러오지만, 불러오지 않았으면 기존 문서를 그대로 둠
+        let Some(embedder) = models.loaded_embedder() else {
+            for job in batch {
+                let (hit, started) = match job {
+                    StoreJob::Index { hit, started, .. }
+                    | StoreJob::Unsupported { hit, started } => (hit, started),
+                };
+                let mut file = FileReport::failed(
+                    hit.path.clone(),
+                    String::from("embedding model is not loaded"),
+                );
+                file.duration = started.elapsed();
+                let update = ManifestUpdate::Keep;
+                if processed
+                    .blocking_send(Processed::Finished { hit, file, update })
+                    .is_err()
+                {
+                    return Ok(());
+                }
+            }
+            continue;
+        };
+        let results = Python::with_gil(|py| store_batch(py, embedder, batch));
+        unsaved += results.len();
+        for result in results {
+            // record 단계가 종료된 경우
+            if processed.blocking_send(result).is_err() {
+                return Ok(());
+            }
+        }
+        if unsaved >= CHECKPOINT_INTERVAL {
+            Python::with_gil(|py| embedder.flush(py))?;
+            let _ = processed.blocking_send(Processed::Flushed);
+            unsaved = 0;
+        }
+    }
+
+    if let Some(embedder) = models.loaded_embedder().filter(|_| unsaved > 0) {
+        Python::with_gil(|py| embedder.flush(py))?;
+        let _ = processed.blocking_send(Processed::Flushed);
+    }
+
+    Ok(())
+}
+
+/// 여러 파일의 문서 조각을 한 번에 임베딩한 후, 파일마다 기존 문서를 삭제하고 저장하는 함수
+fn store_batch(py: Python<'_>, embedder: &Embedder, batch: Vec<StoreJob>) -> Vec<Processed> {
+    let chunks = batch
+        .iter()
+        .filter_map(|job| match job {
+            StoreJob::Index { chunks, .. } => Some(&chunks.chunks),
+            StoreJob::Unsupported { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    let mut embeddings = match embedder.embed_batch(py, &chunks) {
+        Ok(embeddings) => Ok(embeddings.into_iter()),
+        Err(e) => Err(format_py_error(py, &e)),
+    };
+
+    batch
+        .into_iter()
+        .map(|job| match job {
+            StoreJob::Index {
+                hit,
+                hash,
+                loader,
+                chunks,
+                started,
+            } => {
+                let stored = match embeddings.as_mut().map(Iterator::next) {
+                    Ok(Some(embeddings)) => embedder
+                        .remove(py, &hit.path)
+                        .and_then(|_| {
+                            embedder.store_documents(py, &hit, &chunks.chunks, &embeddings)
+                        })
+                        .map_err(|e| (format_py_error(py, &e), ManifestUpdate::Remove)),
+                    // 임베딩에 실패하면 기존 문서를 삭제하지 않음
+                    Ok(None) => Err((
+                        String::from("missing embeddings for file"),
+                        ManifestUpdate::Keep,
+                    )),
+                    Err(e) => Err((e.clone(), ManifestUpdate::Keep)),
+                };
+                let (mut file, update) = match stored {
+                    Ok(chunk_ids) => (
+                        FileReport {
+                            loader: Some(loader.name().to_string()),
+                            pages: Some(chunks.pages),
+                            chunks: chunk_ids.len(),
+                            ocr: chunks.ocr,
+                            caption: chunks.caption,
+                            ..FileReport::new(hit.path.clone(), FileStatus::Indexed)
+                        },
+                        ManifestUpdate::Insert { hash, chunk_ids },
+                    ),
+                    Err((e, update)) => {
+                        eprintln!("Failed to embed {}: {e}", hit.path.display());
+                        (FileReport::failed(hit.path.clone(), e), update)
+                    }
+                };
+                file.duration = started.elapsed();
+                Processed::Finished { hit, file, update }
+            }
+            StoreJob::Unsupported { hit, started } => {
+                // 지원하던 형식의 파일이 다른 형식으로 바뀐 경우 기존 문서 삭제
+                let (mut file, update) = match embedder.remove(py, &hit.path) {
+                    Ok(_) => (
+                        FileReport {
+                            error: Some(format!("unsupported file type {}", hit.mime)),
+                            ..FileReport::new(hit.path.clone(), FileStatus::Unsupported)
+                        },
+                        ManifestUpdate::Remove,
+                    ),
+                    Err(e) => (
+                        FileReport::failed(hit.path.clone(), format_py_error(py, &e)),
+                        ManifestUpdate::Keep,
+                    ),
+                };
+                file.duration = started.elapsed();
+                Processed::Finished { hit, file, update }
+            }
+        })
+        .collect()
+}
+
+/// record 단계: 처리 결과를 `manifest`와 `IngestReport`에 기록하고 진행 상황을 전달하는 함수
+///
+/// `Processed::Flushed`를 받을 때마다 `manifest`를 저장한다.
+fn record_files(
+    manifest: &mut Manifest,
+    mut processed: mpsc::Receiver<Processed>,
+    discovery: &DiscoveryState,
+    cancel: &CancelToken,
+    on_progress: ProgressCallback<'_>,
+) -> Result<IngestReport, PipelineError> {
+    let mut report = IngestReport::default();
+    let started = Instant::now();
+    let mut progress = Progress::default();
+    // 바뀐 파일을 처음 처리하기 시작한 시각 (처리 속도와 남은 시간 계산에 사용)
+    let mut indexing_started = None;
+    let update_progress = |progress: &mut Progress, indexing_started: Option<Instant>| {
+        progress.discovered = discovery.discovered.load(Ordering::Relaxed);
+        progress.discovery_done = discovery.done.load(Ordering::Relaxed);
+        progress.elapsed = started.elapsed();
+        progress.indexing_elapsed = indexing_started.map_or(Duration::ZERO, |i| i.elapsed());
+    };
+    while let Some(processed) = processed.blocking_recv() {
+        let (file, skipped) = match processed {
+            Processed::Started(path) => {
+                indexing_started.get_or_insert_with(Instant::now);
+                progress.started += 1;
+                update_progress(&mut progress, indexing_started);
+                on_progress(&ProgressEvent::FileStarted { path: &path }, &progress);
+                continue;
+            }
+            Processed::Flushed => {
+                manifest.save()?;
+                continue;
+            }
+            Processed::Skipped { hit, file, touched } => {
+                if touched {
+                    manifest.touch(&hit);
+                }
+                (file, true)
+            }
+            Processed::Finished { hit, file, update } => {
+                match update {
+                    ManifestUpdate::Insert { hash, chunk_ids } => {
+                        manifest.insert(&hit, hash, chunk_ids, LOADER_VERSION)
+                    }
+                    ManifestUpdate::Remove => {
+                        manifest.remove(&hit.path);
+                    }
+                    ManifestUpdate::Keep => {}
+                }
+                (file, false)
+            }
+        };
+
+        progress.record(&file, skipped);
+        update_progress(&mut progress, indexing_started);
+        on_progress(&ProgressEvent::FileFinished { file: &file }, &progress);
+        report.push(file);
+    }
+    report.cancelled = cancel.is_cancelled();
+    update_progress(&mut progress, indexing_started);
+    on_progress(&ProgressEvent::Finished, &progress);
+
+    Ok(report)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// `Vector Store`에 바뀐 내용을 저장한 후 `Manifest`를 저장하는 함수
+///
+/// `Vector Store`를 먼저 저장하므로, 그 사이에 종료되면 `Manifest`에 없는 파일은 다음 실행에서
+/// 기존 문서를 삭제하고 다시 저장된다. `embedder`가 없으면 `Manifest`만 저장한다.
+pub fn flush(embedder: Option<&Embedder>, manifest: &mut Manifest) -> Result<(), PipelineError> {
+    if let Some(embedder) = embedder {
+        Python::with_gil(|py| embedder.flush(py))?;
+    }
+    manifest.save()?;
+
+    Ok(())
+}
+
+/// `hit.mime`을 확장자로 추정한 값에서 파일 내용으로 판별한 값으로 바꾸는 함수
+pub fn detect_mime(hit: &mut FileHit) -> std::io::Result<()> {
+    hit.mime = loader::detect_mime(&hit.path, hit.mime)?;
+    Ok(())
+}
+
+/// 바뀐 파일이면 해시를 반환하고, 아니면 상태를 기록한 후 처리 결과를 `Err`로 반환하는 함수
+pub fn changed_hash(
+    manifest: &mut Manifest,
+    hit: &FileHit,
+    state: std::io::Result<FileState>,
+) -> Result<String, FileReport> {
+    check_state(hit, state, || manifest.touch(hit))
+}
+
+/// 바뀐 파일이면 해시를 반환하고, 아니면 처리 결과를 `Err`로 반환하는 함수
+///
+/// 수정 시각만 바뀐 경우 `touch`를 호출한다.
+fn check_state(
+    hit: &FileHit,
+    state: std::io::Result<FileState>,
+    touch: impl FnOnce(),
+) -> Result<String, FileReport> {
+    match state {
+        Ok(FileState::New { hash } | FileState::Modified { hash }) => Ok(hash),
+        Ok(FileState::Touched) => {
+            touch();
+            Err(FileReport::new(hit.path.clone(), FileStatus::Unchanged))
+        }
+        Ok(FileState::Unchanged) => Err(FileReport::new(hit.path.clone(), FileStatus::Unchanged)),
+        Err(e) => {
+            eprintln!("Failed to read {}: {e}", hit.path.display());
+            Err(FileReport::failed(
+                hit.path.clone(),
+                format!("failed to read file: {e}"),
+            ))
+        }
+    }
+}
+
+/// 바뀐 파일의 기존 문서를 삭제하고 다시 저장하는 함수
+///
+/// `hit.mime`에 맞는 로더가 없으면 기존 문서만 삭제하고 `FileStatus::Unsupported`를 반환한다.
+pub fn index_file(
+    py: Python<'_>,
+    document_loader: &DocumentLoader,
+    embedder: &Embedder,
+    manifest: &mut Manifest,
+    hit: &FileHit,
+    hash: String,
+) -> FileReport {
+    let started = Instant::now();
+    let Some(loader) = Loader::for_mime(hit.mime) else {
+        eprintln!("Skipping {} ({})", hit.name, hit.mime);
+        // 지원하던 형식의 파일이 다른 형식으로 바뀐 경우 기존 문서 삭제
+        let mut file = match embedder.remove(py, &hit.path) {
+            Ok(_) => {
+                manifest.remove(&hit.path);
+                FileReport {
+                    error: Some(format!("unsupported file type {}", hit.mime)),
+                    ..FileReport::new(hit.path.clone(), FileStatus::Unsupported)
+                }
+            }
+            Err(e) => FileReport::failed(hit.path.clone(), format_py_error(py, &e)),
+        };
+        file.duration = started.elapsed();
+        return file;
+    };
+
+    eprintln!("Parsing {} ({:?}, {})", hit.name, hit.source, hit.mime);
+    let mut file = match embedder
+        .remove(py, &hit.path)
+        .and_then(|_| embed::embed_file(py, document_loader, embedder, hit, loader))
+    {
+        Ok(embedded) => {
+            let file = FileReport {
+                loader: Some(loader.name().to_string()),
+                pages: Some(embedded.pages),
+                chunks: embedded.chunk_ids.len(),
+                ocr: embedded.ocr,
+                caption: embedded.caption,
+                ..FileReport::new(hit.path.clone(), FileStatus::Indexed)
+            };
+            manifest.insert(hit, hash, embedded.chunk_ids, LOADER_VERSION);
+            file
+        }
+        Err(e) => {
+            eprintln!("Failed to embed {}: {e}", hit.path.display());
+            // 기존 문서가 이미 삭제되었을 수 있으므로 다음 실행에서 다시 저장하도록 기록도 삭제
+            manifest.remove(&hit.path);
+            FileReport::failed(hit.path.clone(), format_py_error(py, &e))
+        }
+    };
+    file.duration = started.elapsed();
+
+    file
+}
@@ -0,0 +1,222 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{
+    loader::Loader,
+    manifest::{FileState, Manifest},
+    native::FileHit,
+    pdf_reader,
+};
+
+/// 저장할 PDF 파일의 페이지당 예상 문서 조각 수
+///
+/// `split_documents`의 문서 조각 크기(500자, 100자 중첩)로 페이지당 약 1,600자를 가정한다.
+const ESTIMATED_CHUNKS_PER_PAGE: usize = 4;
+/// 저장할 이미지 파일의 예상 문서 조각 수 (캡션, OCR)
+const ESTIMATED_CHUNKS_PER_IMAGE: usize = 2;
+
+/// `index`를 실행하면 파일에 적용될 작업
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlannedAction {
+    /// 새로 저장
+    Add,
+    /// 기존 문서를 삭제하고 다시 저장
+    Update,
+    /// 바뀌지 않아 건너뜀
+    Skip,
+    /// 삭제된 파일의 문서를 삭제
+    Remove,
+    /// 문서를 추출할 로더가 없어 건너뜀
+    Unsupported,
+    /// 파일을 읽을 수 없음
+    Failed,
+}
+
+impl PlannedAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Update => "update",
+            Self::Skip => "skip",
+            Self::Remove => "remove",
+            Self::Unsupported => "unsupported",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// 파일 하나에 적용될 작업
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedFile {
+    pub path: PathBuf,
+    pub action: PlannedAction,
+    /// 파일 내용으로 판별한 MIME 타입 (삭제된 파일은 `None`)
+    pub mime: Option<&'static str>,
+    /// 예상 페이지 수 (PDF 파일은 실제 페이지 수)
+    pub pages: Option<usize>,
+    /// 저장할 파일은 예상 문서 조각 수, 건너뛰거나 삭제할 파일은 저장된 문서 조각 수
+    pub chunks: Option<usize>,
+    pub error: Option<String>,
+}
+
+impl PlannedFile {
+    /// `check` 단계에서 확인한 파일 상태로 작업을 정하는 함수
+    pub fn new(manifest: &Manifest, hit: &FileHit, state: std::io::Result<FileState>) -> Self {
+        let stored_chunks = manifest.get(&hit.path).map(|entry| entry.chunk_ids.len());
+        let mut file = Self {
+            path: hit.path.clone(),
+            action: PlannedAction::Skip,
+            mime: Some(hit.mime),
+            pages: None,
+            chunks: stored_chunks,
+            error: None,
+        };
+
+        match state {
+            Ok(FileState::Unchanged | FileState::Touched) => {}
+            Ok(FileState::New { .. } | FileState::Modified { .. }) => {
+                match Loader::for_mime(hit.mime) {
+                    Some(loader) => {
+                        file.action = if stored_chunks.is_some() {
+                            PlannedAction::Update
+                        } else {
+                            PlannedAction::Add
+                        };
+                        file.pages = estimate_pages(loader, &hit.path);
+                        // 다시 저장하면 기존 문서 조각은 삭제되므로 새 파일과 같이 예상
+                        file.chunks = Some(estimate_chunks(loader, file.pages));
+                    }
+                    None => file.action = PlannedAction::Unsupported,
+                }
+            }
+            Err(e) => {
+                file.action = PlannedAction::Failed;
+                file.error = Some(format!("failed to read file: {e}"));
+            }
+        }
+
+        file
+    }
+
+    /// `manifest`에 기록되어 있지만 삭제된 파일의 작업을 만드는 함수
+    pub fn removed(manifest: &Manifest, path: PathBuf) -> Self {
+        Self {
+            chunks: manifest.get(&path).map(|entry| entry.chunk_ids.len()),
+            path,
+            action: PlannedAction::Remove,
+            mime: None,
+            pages: None,
+            error: None,
+        }
+    }
+}
+
+/// `index --dry-run`의 결과
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Preview {
+    pub files: Vec<PlannedFile>,
+    /// 모든 파일을 확인하기 전에 취소되었는지 여부
+    pub cancelled: bool,
+}
+
+impl Preview {
+    pub fn push(&mut self, file: PlannedFile) {
+        self.files.push(file);
+    }
+
+    pub fn count(&self, action: PlannedAction) -> usize {
+        self.files
+            .iter()
+            .filter(|file| file.action == action)
+            .count()
+    }
+
+    /// 저장할 파일들의 예상 (페이지 수, 문서 조각 수)
+    pub fn estimated_totals(&self) -> (usize, usize) {
+        self.files
+            .iter()
+            .filter(|file| matches!(file.action, PlannedAction::Add | PlannedAction::Update))
+            .fold((0, 0), |(pages, chunks), file| {
+                (
+                    pages + file.pages.unwrap_or_default(),
+                    chunks + file.chunks.unwrap_or_default(),
+                )
+            })
+    }
+
+    pub fn summary(&self) -> String {
+        let (pages, chunks) = self.estimated_totals();
+        format!(
+            "Would add {} and update {} files (about {pages} pages, {chunks} chunks), remove {}, skip {} ({} unsupported, {} failed)",
+            self.count(PlannedAction::Add),
+            self.count(PlannedAction::Update),
+            self.count(PlannedAction::Remove),
+            self.count(PlannedAction::Skip),
+            self.count(PlannedAction::Unsupported),
+            self.count(PlannedAction::Failed)
+        )
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let (pages, chunks) = self.estimated_totals();
+        serde_json::json!({
+            "add": self.count(PlannedAction::Add),
+            "update": self.count(PlannedAction::Update),
+            "remove": self.count(PlannedAction::Remove),
+            "skip": self.count(PlannedAction::Skip),
+            "unsupported": self.count(PlannedAction::Unsupported),
+            "failed": self.count(PlannedAction::Failed),
+            "estimated_pages": pages,
+            "estimated_chunks": chunks,
+            "cancelled": self.cancelled,
+            "files": self.files,
+        })
+    }
+
+    /// 건너뛰는 파일을 제외한 작업을 표로 출력하는 함수
+    pub fn print_table(&self) {
+        let files = self
+            .files
+            .iter()
+            .filter(|file| file.action != PlannedAction::Skip)
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return;
+        }
+
+        println!(
+            "{:<11}  {:>5}  {:>6}  {:<24}  PATH",
+            "ACTION", "PAGES", "CHUNKS", "TYPE"
+        );
+        let count =
+            |value: Option<usize>| value.map_or(String::from("-"), |value| value.to_string());
+        for file in files {
+            println!(
+                "{:<11}  {:>5}  {:>6}  {:<24}  {}",
+                file.action.as_str(),
+                count(file.pages),
+                count(file.chunks),
+                file.mime.unwrap_or("-"),
+                file.path.display()
+            );
+        }
+    }
+}
+
+/// 모델을 불러오지 않고 파일의 페이지 수를 계산하는 함수 (PDF를 읽을 수 없으면 `None`)
+fn estimate_pages(loader: Loader, path: &Path) -> Option<usize> {
+    match loader {
+        Loader::Pdf => pdf_reader::page_count(path).ok(),
+        Loader::Image => Some(1),
+    }
+}
+
+/// 저장할 파일의 예상 문서 조각 수
+fn estimate_chunks(loader: Loader, pages: Option<usize>) -> usize {
+    match loader {
+        Loader::Pdf => pages.unwrap_or(1) * ESTIMATED_CHUNKS_PER_PAGE,
+        Loader::Image => ESTIMATED_CHUNKS_PER_IMAGE,
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::report::{serialize_millis, FileReport, FileStatus};
+
+/// 터미널에 진행 상황을 출력하는 최소 간격
+const TERMINAL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 파일 저장 진행 상황
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Progress {
+    /// 지금까지 검색된 파일 수 (검색이 끝나지 않았으면 늘어날 수 있음)
+    pub discovered: usize,
+    /// 파일 검색이 끝났는지 여부
+    pub discovery_done: bool,
+    /// 처리를 마친 파일 수 (바뀌지 않은 파일 포함)
+    pub processed: usize,
+    /// 바뀌지 않았거나 읽을 수 없어 처리를 시작하지 않고 건너뛴 파일 수
+    pub skipped: usize,
+    /// 바뀐 파일 중 처리를 시작한 파일 수
+    pub started: usize,
+    pub indexed: usize,
+    pub failed: usize,
+    /// 저장한 파일들에서 추출한 페이지 수
+    pub pages: usize,
+    /// 저장한 문서 조각 수
+    pub chunks: usize,
+    #[serde(rename = "elapsed_ms", serialize_with = "serialize_millis")]
+    pub elapsed: Duration,
+    /// 바뀐 파일을 처음 처리하기 시작한 후 지난 시간
+    #[serde(rename = "indexing_elapsed_ms", serialize_with = "serialize_millis")]
+    pub indexing_elapsed: Duration,
+}
+
+impl Progress {
+    /// 처리를 마친 파일을 반영하는 함수 (`skipped`: 처리를 시작하지 않고 건너뛴 파일인지 여부)
+    pub fn record(&mut self, file: &FileReport, skipped: bool) {
+        self.processed += 1;
+        if skipped {
+            self.skipped += 1;
+        }
+        match file.status {
+            FileStatus::Indexed => self.indexed += 1,
+            FileStatus::Failed => self.failed += 1,
+            FileStatus::Unchanged | FileStatus::Removed | FileStatus::Unsupported => {}
+        }
+        self.pages += file.pages.unwrap_or_default();
+        self.chunks += file.chunks;
+    }
+
+    /// 바뀐 파일 중 처리를 마친 파일 수
+    fn worked(&self) -> usize {
+        self.processed - self.skipped
+    }
+
+    /// 바뀐 파일을 처음 처리하기 시작한 후 초당 처리한 파일 수
+    ///
+    /// 건너뛴 파일은 해시만 비교하고 바로 처리를 마치므로 포함하지 않는다.
+    pub fn files_per_sec(&self) -> f64 {
+        self.worked() as f64 / self.indexing_elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// 바뀐 파일을 처음 처리하기 시작한 후 초당 추출한 페이지 수
+    pub fn pages_per_sec(&self) -> f64 {
+        self.pages as f64 / self.indexing_elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// 바뀐 파일의 평균 처리 시간으로 계산한 남은 시간 (처리를 마친 바뀐 파일이 없으면 `None`)
+    ///
+    /// 처리 중인 바뀐 파일과, 아직 상태를 확인하지 않은 파일 중 지금까지의 비율만큼 바뀌었을
+    /// 파일을 남은 작업으로 계산한다. 검색이 끝나지 않았으면 지금까지 검색된 파일만으로 계산한다.
+    pub fn eta(&self) -> Option<Duration> {
+        let worked = self.worked();
+        if worked == 0 {
+            return None;
+        }
+        let in_progress = self.started.saturating_sub(worked);
+        let unchecked = self
+            .discovered
+            .saturating_sub(self.processed)
+            .saturating_sub(in_progress);
+        let remaining =
+            in_progress as f64 + unchecked as f64 * worked as f64 / self.processed as f64;
+        Some(self.indexing_elapsed.mul_f64(remaining / worked as f64))
+    }
+}
+
+/// 진행 상황 콜백으로 전달되는 사건
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent<'a> {
+    /// 바뀐 파일을 `Python`으로 저장하기 시작함
+    FileStarted { path: &'a Path },
+    /// 파일 하나를 처리함 (바뀌지 않은 파일 포함)
+    FileFinished { file: &'a FileReport },
+    /// 검색된 모든 파일을 처리함
+    Finished,
+}
+
+/// 진행 상황을 표준 에러에 출력하는 콜백
+///
+/// 파일마다 출력하면 너무 많으므로 `TERMINAL_INTERVAL`마다 한 줄씩 출력한다.
+#[derive(Debug, Default)]
+pub struct TerminalProgress {
+    last_printed: Option<Instant>,
+}
+
+impl TerminalProgress {
+    pub fn report(&mut self, event: &ProgressEvent<'_>, progress: &Progress) {
+        match event {
+            ProgressEvent::FileStarted { .. } => {}
+            ProgressEvent::FileFinished { .. } => {
+                if self
+                    .last_printed
+                    .is_some_and(|printed| printed.elapsed() < TERMINAL_INTERVAL)
+                {
+                    return;
+                }
+                self.last_printed = Some(Instant::now());
+                eprintln!(
+                    "Progress: {}/{}{} files, {} pages, {} chunks, {:.1} files/s, {:.1} pages/s, ETA {}",
+                    progress.processed,
+                    progress.discovered,
+                    if progress.discovery_done { "" } else { "+" },
+                    progress.pages,
+                    progress.chunks,
+                    progress.files_per_sec(),
+                    progress.pages_per_sec(),
+                    progress.eta().map_or(String::from("?"), format_duration)
+                );
+            }
+            ProgressEvent::Finished => eprintln!(
+                "Processed {} files ({} skipped, {} pages, {} chunks) in {}, {:.1} files/s, {:.1} pages/s",
+                progress.processed,
+                progress.skipped,
+                progress.pages,
+                progress.chunks,
+                format_duration(progress.elapsed),
+                progress.files_per_sec(),
+                progress.pages_per_sec()
+            ),
+        }
+    }
+}
+
+/// 시간을 `1h 2m`, `3m 4s`, `5s` 형식으로 만드는 함수
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s}s"),
+        (h, m, _) => format!("{h}h {m}m"),
+    }
+}
@@ -0,0 +1,181 @@
+use std::io::{BufRead, Write};
+
+use pyo3::{PyResult, Python};
+
+use crate::{
+    config::Config,
+    embed::{Answerer, Models, RetrievedChunk},
+};
+
+/// 질문 하나에 보여줄 문서 조각 수
+const TOP_K: usize = 5;
+/// 문서 조각의 내용을 보여줄 최대 글자 수
+const PREVIEW_LENGTH: usize = 200;
+
+/// 대화형 루프의 명령어
+enum Command {
+    /// `Vector Store`에서 검색할 질문
+    Query(String),
+    /// `:reindex` 파일을 다시 검색하여 저장
+    Reindex,
+    /// `:sources` 저장된 파일 목록 출력
+    Sources,
+    /// `:answer` LLM 답변 생성 여부 전환
+    Answer,
+    /// `:help` 명령어 목록 출력
+    Help,
+    /// `:quit` 종료
+    Quit,
+    Unknown(String),
+}
+
+impl Command {
+    /// 입력 한 줄을 명령어로 변환하는 함수 (빈 줄은 `None`)
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        let command = match line {
+            ":reindex" => Self::Reindex,
+            ":sources" => Self::Sources,
+            ":answer" => Self::Answer,
+            ":help" => Self::Help,
+            ":quit" | ":q" => Self::Quit,
+            _ if line.starts_with(':') => Self::Unknown(line.to_string()),
+            _ => Self::Query(line.to_string()),
+        };
+        Some(command)
+    }
+}
+
+/// `Vector Store`에 질문을 검색하는 대화형 루프를 실행하는 함수
+///
+/// 표준 입력이 끝나거나(EOF) `:quit`을 입력하면 반환한다.
+/// `:reindex`를 입력하면 `reindex`로 파일을 다시 저장한다.
+pub fn run(
+    py: Python<'_>,
+    config: &Config,
+    models: &Models,
+    mut reindex: impl FnMut(Python<'_>, &Models),
+) -> PyResult<()> {
+    let mut answerer: Option<Answerer> = None;
+    let mut answering = false;
+    print_help();
+
+    let stdin = std::io::stdin();
+    loop {
+        print!("Contents Query > ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+        let Some(command) = Command::parse(&line) else {
+            continue;
+        };
+
+        match command {
+            Command::Query(question) => {
+                let embedder = models.embedder(py, config)?;
+                let chunks = match embedder.search(py, &question, TOP_K) {
+                    Ok(chunks) => chunks,
+                    Err(e) => {
+                        println!("Failed to search: {e}");
+                        continue;
+                    }
+                };
+                print_chunks(&chunks);
+                if answering && !chunks.is_empty() {
+                    print_answer(py, config, &mut answerer, &question, &chunks);
+                }
+            }
+            Command::Reindex => reindex(py, models),
+            Command::Sources => {
+                let embedder = models.embedder(py, config)?;
+                match embedder.sources(py) {
+                    Ok(sources) => {
+                        for (path, count) in &sources {
+                            println!("{count:>5} chunks  {}", path.display());
+                        }
+                        println!("{} files", sources.len());
+                    }
+                    Err(e) => println!("Failed to read sources: {e}"),
+                }
+            }
+            Command::Answer => {
+                answering = !answering;
+                println!("LLM answer: {}", if answering { "on" } else { "off" });
+            }
+            Command::Help => print_help(),
+            Command::Quit => break,
+            Command::Unknown(command) => println!("Unknown command: {command} (:help)"),
+        }
+    }
+
+    Ok(())
+}
+
+fn print_help() {
+    println!("Type a question to search the vector store, or a command:");
+    println!("  :reindex  search and store files again");
+    println!("  :sources  list stored files");
+    println!("  :answer   toggle LLM answers (off by default)");
+    println!("  :quit     exit (or EOF)");
+}
+
+/// 검색된 문서 조각을 관련도 순서대로 출력하는 함수
+pub fn print_chunks(chunks: &[RetrievedChunk]) {
+    if chunks.is_empty() {
+        println!("No matching documents.");
+        return;
+    }
+    for (rank, chunk) in chunks.iter().enumerate() {
+        let location = match (chunk.page, chunk.offset) {
+            (Some(page), Some(offset)) => format!(" p.{page} @{offset}"),
+            (Some(page), None) => format!(" p.{page}"),
+            (None, _) => String::new(),
+        };
+        println!(
+            "[{}] {:.3}  {}{location}",
+            rank + 1,
+            chunk.score,
+            chunk.path.display()
+        );
+        // 여러 줄의 내용을 한 줄로 합쳐서 앞부분만 출력
+        let preview = chunk
+            .content
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let preview = preview.chars().take(PREVIEW_LENGTH).collect::<String>();
+        println!("    {preview}");
+    }
+}
+
+/// 검색된 문서 조각으로 LLM 답변을 생성하여 출력하는 함수
+fn print_answer(
+    py: Python<'_>,
+    config: &Config,
+    answerer: &mut Option<Answerer>,
+    question: &str,
+    chunks: &[RetrievedChunk],
+) {
+    if answerer.is_none() {
+        match Answerer::load(py, config) {
+            Ok(loaded) => *answerer = Some(loaded),
+            Err(e) => {
+                println!("Failed to load LLM: {e}");
+                return;
+            }
+        }
+    }
+    let Some(answerer) = answerer.as_ref() else {
+        return;
+    };
+
+    match answerer.answer(py, question, chunks) {
+        Ok(answer) => println!("Answer: {}", answer.trim()),
+        Err(e) => println!("Failed to answer: {e}"),
+    }
+}
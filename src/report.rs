@@ -0,0 +1,171 @@
+use std::{path::PathBuf, time::Duration};
+
+use serde::{Serialize, Serializer};
+
+/// 파일 하나의 처리 결과
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    /// 문서를 추출한 로더 이름
+    pub loader: Option<String>,
+    /// 추출한 페이지 수 (이미지는 1)
+    pub pages: Option<usize>,
+    /// `Vector Store`에 저장한 문서 조각 수 (삭제한 경우 삭제한 조각 수)
+    pub chunks: usize,
+    /// 이미지에서 OCR로 글자를 추출했는지 여부
+    pub ocr: bool,
+    /// 이미지 캡션을 생성했는지 여부
+    pub caption: bool,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration,
+    /// 실패한 경우 오류 메시지 (`Python` 오류는 traceback 포함) 또는 지원하지 않는 이유
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    /// 새로 저장하거나 다시 저장한 파일
+    Indexed,
+    /// 저장된 후 바뀌지 않아 건너뛴 파일
+    Unchanged,
+    /// 삭제되어 `Vector Store`에서도 삭제한 파일
+    Removed,
+    /// 문서를 추출할 로더가 없는 형식의 파일 (`loader::LOADERS`)
+    Unsupported,
+    Failed,
+}
+
+impl FileStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Indexed => "indexed",
+            Self::Unchanged => "unchanged",
+            Self::Removed => "removed",
+            Self::Unsupported => "unsupported",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl FileReport {
+    pub fn new(path: PathBuf, status: FileStatus) -> Self {
+        Self {
+            path,
+            status,
+            loader: None,
+            pages: None,
+            chunks: 0,
+            ocr: false,
+            caption: false,
+            duration: Duration::ZERO,
+            error: None,
+        }
+    }
+
+    pub fn failed(path: PathBuf, error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(path, FileStatus::Failed)
+        }
+    }
+}
+
+/// 여러 파일의 처리 결과
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IngestReport {
+    pub files: Vec<FileReport>,
+    /// 모든 파일을 처리하기 전에 취소되었는지 여부
+    pub cancelled: bool,
+}
+
+impl IngestReport {
+    pub fn push(&mut self, file: FileReport) {
+        self.files.push(file);
+    }
+
+    pub fn count(&self, status: FileStatus) -> usize {
+        self.files
+            .iter()
+            .filter(|file| file.status == status)
+            .count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} files... ({} unchanged, {} removed, {} unsupported, {} failed)",
+            self.count(FileStatus::Indexed),
+            self.count(FileStatus::Unchanged),
+            self.count(FileStatus::Removed),
+            self.count(FileStatus::Unsupported),
+            self.count(FileStatus::Failed)
+        )
+    }
+
+    /// 파일별 결과와 전체 수를 JSON으로 변환하는 함수
+    pub fn to_json(&self, elapsed: Duration) -> serde_json::Value {
+        serde_json::json!({
+            "indexed": self.count(FileStatus::Indexed),
+            "unchanged": self.count(FileStatus::Unchanged),
+            "removed": self.count(FileStatus::Removed),
+            "unsupported": self.count(FileStatus::Unsupported),
+            "failed": self.count(FileStatus::Failed),
+            "elapsed_ms": elapsed.as_millis(),
+            "cancelled": self.cancelled,
+            "files": self.files,
+        })
+    }
+
+    /// 바뀐 파일들의 결과를 표로 출력하고, 실패한 파일의 오류를 이어서 출력하는 함수
+    ///
+    /// 바뀌지 않은 파일은 수가 많을 수 있으므로 표에서 제외한다.
+    pub fn print_table(&self) {
+        let files = self
+            .files
+            .iter()
+            .filter(|file| file.status != FileStatus::Unchanged)
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return;
+        }
+
+        println!(
+            "{:<11}  {:>6}  {:>5}  {:<10}  {:<11}  {:>8}  PATH",
+            "STATUS", "CHUNKS", "PAGES", "LOADER", "OCR/CAPTION", "TIME"
+        );
+        for file in &files {
+            let extraction = match (file.ocr, file.caption) {
+                (true, true) => "ocr+caption",
+                (true, false) => "ocr",
+                (false, true) => "caption",
+                (false, false) => "-",
+            };
+            println!(
+                "{:<11}  {:>6}  {:>5}  {:<10}  {:<11}  {:>8}  {}",
+                file.status.as_str(),
+                file.chunks,
+                file.pages
+                    .map_or(String::from("-"), |pages| pages.to_string()),
+                file.loader.as_deref().unwrap_or("-"),
+                extraction,
+                format!("{:.2?}", file.duration),
+                file.path.display()
+            );
+        }
+
+        for file in files.iter().filter(|file| file.error.is_some()) {
+            println!();
+            println!("Failed {}:", file.path.display());
+            println!("{}", file.error.as_deref().unwrap_or_default().trim_end());
+        }
+    }
+}
+
+/// `Duration`을 밀리초 정수로 직렬화하는 함수
+pub fn serialize_millis<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_millis())
+}
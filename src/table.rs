@@ -0,0 +1,347 @@
+use crate::layout::{self, TextRun};
+
+/// 같은 선으로 보는 좌표 차이, 괘선으로 보는 최대 두께 (pt)
+const RULING_TOLERANCE: f32 = 2.0;
+/// 같은 행으로 보는 기준선 높이 차이 (글자 크기 대비)
+const ROW_TOLERANCE: f32 = 0.5;
+/// 괘선이 없는 표에서 다른 칸으로 보는 글자열 사이 간격 (글자 크기 대비)
+const CELL_GAP: f32 = 1.0;
+/// 괘선이 없는 표에서 같은 표로 보는 행 사이 최대 기준선 간격 (글자 크기 대비)
+const MAX_ROW_GAP: f32 = 2.5;
+/// 괘선이 없는 표의 최소 행 수 (머리글 포함)
+const MIN_ALIGNED_ROWS: usize = 3;
+/// 괘선이 없는 표에서 칸으로 보는 최대 글자 수 (여러 단으로 된 본문을 표로 보지 않기 위함)
+const MAX_ALIGNED_CELL_LENGTH: usize = 40;
+
+/// 페이지 좌표의 수평 또는 수직 괘선
+#[derive(Debug, Clone, Copy)]
+pub struct Ruling {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+}
+
+impl Ruling {
+    /// 두 점을 잇는 괘선 (수평선이나 수직선이 아니면 `None`)
+    pub fn new((x0, y0): (f32, f32), (x1, y1): (f32, f32)) -> Option<Self> {
+        let ruling = Self {
+            x0: x0.min(x1),
+            y0: y0.min(y1),
+            x1: x0.max(x1),
+            y1: y0.max(y1),
+        };
+        (ruling.is_horizontal() != ruling.is_vertical()).then_some(ruling)
+    }
+
+    fn is_horizontal(&self) -> bool {
+        self.y1 - self.y0 <= RULING_TOLERANCE && self.x1 - self.x0 > RULING_TOLERANCE
+    }
+
+    fn is_vertical(&self) -> bool {
+        self.x1 - self.x0 <= RULING_TOLERANCE && self.y1 - self.y0 > RULING_TOLERANCE
+    }
+
+    /// 수평선과 수직선이 만나거나 끝이 닿는지 여부
+    fn crosses(&self, other: &Self) -> bool {
+        self.x0 - RULING_TOLERANCE <= other.x1
+            && other.x0 - RULING_TOLERANCE <= self.x1
+            && self.y0 - RULING_TOLERANCE <= other.y1
+            && other.y0 - RULING_TOLERANCE <= self.y1
+    }
+}
+
+/// PDF 페이지에서 찾은 표 (첫 행은 머리글)
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// 행마다 한 줄인 Markdown 표
+    ///
+    /// 문서 조각으로 나눌 때 행이 중간에 나뉘지 않도록 칸 안의 줄바꿈은 띄어쓰기로 바꾼다.
+    pub fn to_markdown(&self) -> String {
+        let columns = self.rows.iter().map(Vec::len).max().unwrap_or_default();
+        let cell = |row: &[String], column: usize| {
+            row.get(column)
+                .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+                .unwrap_or_default()
+                .replace('|', "\\|")
+        };
+        let line = |row: &[String]| {
+            let cells = (0..columns)
+                .map(|column| cell(row, column))
+                .collect::<Vec<_>>();
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let mut lines = vec![];
+        for (index, row) in self.rows.iter().enumerate() {
+            lines.push(line(row));
+            if index == 0 {
+                lines.push(format!("|{}", " --- |".repeat(columns)));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// 괘선과 글자 정렬로 페이지의 표를 찾는 함수
+///
+/// 괘선이 없는 표는 여러 단으로 된 본문의 같은 높이 줄들을 표로 보지 않도록,
+/// 페이지를 단으로 나눈 후 단마다 따로 찾는다 (`layout::column_blocks`).
+/// (찾은 표 목록, 표에 포함되지 않은 글자열)을 반환한다.
+pub fn detect_tables(runs: Vec<TextRun>, rulings: &[Ruling]) -> (Vec<Table>, Vec<TextRun>) {
+    let mut tables = vec![];
+    let mut rest = runs;
+    for grid in ruled_grids(rulings) {
+        let (table, remaining) = grid.fill(rest);
+        tables.extend(table);
+        rest = remaining;
+    }
+
+    let mut remaining = vec![];
+    for block in layout::column_blocks(rest) {
+        let (aligned, rest) = aligned_tables(block);
+        tables.extend(aligned);
+        remaining.extend(rest);
+    }
+
+    (tables, remaining)
+}
+
+/// 수평선들의 y 좌표(위에서부터)와 수직선들의 x 좌표(왼쪽부터)로 나뉜 격자
+#[derive(Debug)]
+struct Grid {
+    ys: Vec<f32>,
+    xs: Vec<f32>,
+}
+
+impl Grid {
+    /// 격자 안의 글자열을 칸에 채운 표와 격자 밖의 글자열을 반환하는 함수
+    ///
+    /// 글자열은 가운데가 놓인 칸에 넣으며, 합쳐진 칸은 첫 칸에만 글자가 들어간다.
+    fn fill(&self, runs: Vec<TextRun>) -> (Option<Table>, Vec<TextRun>) {
+        let mut cells = vec![vec![vec![]; self.xs.len() - 1]; self.ys.len() - 1];
+        let mut rest = vec![];
+        for run in runs {
+            let x = run.x + run.width / 2.0;
+            let y = run.y + run.size / 3.0;
+            let row = self.ys.windows(2).position(|w| w[0] >= y && y > w[1]);
+            let column = self.xs.windows(2).position(|w| w[0] <= x && x < w[1]);
+            match (row, column) {
+                (Some(row), Some(column)) => cells[row][column].push(run),
+                _ => rest.push(run),
+            }
+        }
+
+        let rows = cells
+            .into_iter()
+            .map(|row| row.into_iter().map(cell_text).collect::<Vec<_>>())
+            .filter(|row| row.iter().any(|text| !text.is_empty()))
+            .collect::<Vec<_>>();
+        let table = (!rows.is_empty()).then_some(Table { rows });
+
+        (table, rest)
+    }
+}
+
+/// 서로 만나는 괘선들을 묶어 격자를 찾는 함수 (칸이 하나뿐인 테두리는 제외)
+fn ruled_grids(rulings: &[Ruling]) -> Vec<Grid> {
+    // 서로 만나는 괘선끼리 같은 묶음 번호를 갖도록 합침
+    let mut groups = (0..rulings.len()).collect::<Vec<_>>();
+    fn root(groups: &mut [usize], mut i: usize) -> usize {
+        while groups[i] != i {
+            groups[i] = groups[groups[i]];
+            i = groups[i];
+        }
+        i
+    }
+    for i in 0..rulings.len() {
+        for j in i + 1..rulings.len() {
+            if rulings[i].is_horizontal() != rulings[j].is_horizontal()
+                && rulings[i].crosses(&rulings[j])
+            {
+                let (a, b) = (root(&mut groups, i), root(&mut groups, j));
+                groups[a] = b;
+            }
+        }
+    }
+
+    let mut grids = vec![];
+    for group in 0..rulings.len() {
+        let members = (0..rulings.len())
+            .filter(|&i| root(&mut groups, i) == group)
+            .map(|i| rulings[i])
+            .collect::<Vec<_>>();
+        let mut ys = members
+            .iter()
+            .filter(|ruling| ruling.is_horizontal())
+            .map(|ruling| (ruling.y0 + ruling.y1) / 2.0)
+            .collect::<Vec<_>>();
+        let mut xs = members
+            .iter()
+            .filter(|ruling| ruling.is_vertical())
+            .map(|ruling| (ruling.x0 + ruling.x1) / 2.0)
+            .collect::<Vec<_>>();
+        ys.sort_by(|a, b| b.total_cmp(a));
+        xs.sort_by(f32::total_cmp);
+        ys.dedup_by(|a, b| (*a - *b).abs() <= RULING_TOLERANCE);
+        xs.dedup_by(|a, b| (*a - *b).abs() <= RULING_TOLERANCE);
+
+        if ys.len() >= 2 && xs.len() >= 2 && ys.len() + xs.len() > 4 {
+            grids.push(Grid { ys, xs });
+        }
+    }
+
+    grids
+}
+
+/// 괘선이 없는 표의 칸
+#[derive(Debug)]
+struct Cell {
+    left: f32,
+    right: f32,
+    runs: Vec<TextRun>,
+}
+
+/// 같은 기준선의 칸들
+#[derive(Debug)]
+struct Row {
+    y: f32,
+    size: f32,
+    cells: Vec<Cell>,
+}
+
+impl Row {
+    /// 칸이 둘 이상이고 모든 칸이 짧은 행인지 여부
+    fn is_tabular(&self) -> bool {
+        self.cells.len() >= 2
+            && self.cells.iter().all(|cell| {
+                cell.runs
+                    .iter()
+                    .map(|run| run.text.chars().count())
+                    .sum::<usize>()
+                    <= MAX_ALIGNED_CELL_LENGTH
+            })
+    }
+
+    /// 칸 수가 같고 각 칸이 `columns`의 같은 열과 가로로 겹치는지 여부
+    fn aligns_with(&self, columns: &[(f32, f32)]) -> bool {
+        self.cells.len() == columns.len()
+            && self
+                .cells
+                .iter()
+                .zip(columns)
+                .all(|(cell, &(left, right))| cell.left <= right && left <= cell.right)
+    }
+}
+
+/// 칸 위치가 같은 행이 `MIN_ALIGNED_ROWS`개 이상 이어지는 곳을 표로 찾는 함수
+fn aligned_tables(runs: Vec<TextRun>) -> (Vec<Table>, Vec<TextRun>) {
+    let mut tables = vec![];
+    let mut rest = vec![];
+    let mut block: Vec<Row> = vec![];
+    let mut columns: Vec<(f32, f32)> = vec![];
+
+    let mut flush = |block: &mut Vec<Row>, rest: &mut Vec<TextRun>| {
+        if block.len() >= MIN_ALIGNED_ROWS {
+            let rows = block
+                .drain(..)
+                .map(|row| {
+                    row.cells
+                        .into_iter()
+                        .map(|cell| cell_text(cell.runs))
+                        .collect()
+                })
+                .collect();
+            tables.push(Table { rows });
+        } else {
+            rest.extend(
+                block
+                    .drain(..)
+                    .flat_map(|row| row.cells)
+                    .flat_map(|cell| cell.runs),
+            );
+        }
+    };
+
+    for row in rows(runs) {
+        let continues = block.last().is_some_and(|last| {
+            last.y - row.y <= MAX_ROW_GAP * last.size.max(row.size)
+                && row.is_tabular()
+                && row.aligns_with(&columns)
+        });
+        if continues {
+            for (column, cell) in columns.iter_mut().zip(&row.cells) {
+                *column = (column.0.min(cell.left), column.1.max(cell.right));
+            }
+            block.push(row);
+        } else {
+            flush(&mut block, &mut rest);
+            if row.is_tabular() {
+                columns = row
+                    .cells
+                    .iter()
+                    .map(|cell| (cell.left, cell.right))
+                    .collect();
+                block.push(row);
+            } else {
+                rest.extend(row.cells.into_iter().flat_map(|cell| cell.runs));
+            }
+        }
+    }
+    flush(&mut block, &mut rest);
+
+    (tables, rest)
+}
+
+/// 글자열을 위에서부터 행으로 묶고, 행 안에서는 간격이 넓은 곳을 칸으로 나누는 함수
+fn rows(mut runs: Vec<TextRun>) -> Vec<Row> {
+    runs.sort_by(|a, b| b.y.total_cmp(&a.y));
+
+    let mut rows: Vec<Vec<TextRun>> = vec![];
+    for run in runs {
+        match rows.last_mut() {
+            Some(row) if (row[0].y - run.y).abs() <= ROW_TOLERANCE * row[0].size.max(run.size) => {
+                row.push(run)
+            }
+            _ => rows.push(vec![run]),
+        }
+    }
+
+    rows.into_iter()
+        .map(|mut runs| {
+            runs.sort_by(|a, b| a.x.total_cmp(&b.x));
+            let y = runs[0].y;
+            let size = runs.iter().map(|run| run.size).fold(0.0, f32::max);
+            let mut cells: Vec<Cell> = vec![];
+            for run in runs {
+                let right = run.x + run.width;
+                match cells.last_mut() {
+                    Some(cell) if run.x - cell.right <= CELL_GAP * size => {
+                        cell.right = cell.right.max(right);
+                        cell.runs.push(run);
+                    }
+                    _ => cells.push(Cell {
+                        left: run.x,
+                        right,
+                        runs: vec![run],
+                    }),
+                }
+            }
+            Row { y, size, cells }
+        })
+        .collect()
+}
+
+/// 칸 안의 글자열을 위에서부터 띄어쓰기로 이은 글자
+fn cell_text(mut runs: Vec<TextRun>) -> String {
+    runs.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+    runs.iter()
+        .map(|run| run.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
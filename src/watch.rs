@@ -0,0 +1,203 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::mpsc::{self, RecvTimeoutError},
+    time::Duration,
+};
+
+use notify::{event::ModifyKind, Config, EventKind, PollWatcher, RecursiveMode, Watcher};
+
+use crate::native::{CancelToken, FileHit, HitSource, SearchFilter};
+
+/// 변경 이벤트가 없을 때 `WatchOptions::cancel`을 확인하는 주기
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 파일 변경 감시 옵션
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// 감시할 디렉토리 목록 (하위 디렉토리 포함)
+    pub roots: Vec<PathBuf>,
+    /// 마지막 변경 이벤트 후 이 시간 동안 추가 이벤트가 없으면 변경 사항을 처리
+    pub debounce: Duration,
+    /// OS 알림을 사용할 수 없을 때 파일 시스템을 다시 확인하는 주기
+    pub poll_interval: Duration,
+    /// 취소되면 모아 둔 변경 사항을 처리한 후 감시를 중단
+    pub cancel: CancelToken,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            debounce: Duration::from_secs(2),
+            poll_interval: Duration::from_secs(30),
+            cancel: CancelToken::default(),
+        }
+    }
+}
+
+/// 인덱스에 반영해야 하는 파일 변경 사항
+#[derive(Debug, Clone)]
+pub enum FileChange {
+    /// 새로 생성되었거나 수정된 파일 (이름이 변경된 경우 새 경로)
+    Upserted(FileHit),
+    /// 삭제된 파일 또는 디렉토리 (이름이 변경된 경우 이전 경로)
+    Removed(PathBuf),
+}
+
+/// `options.roots`의 파일 변경을 감시하여 `on_changes`로 전달하는 함수
+///
+/// OS의 파일 변경 알림(Linux의 inotify 등)을 우선 사용하고, 사용할 수 없으면
+/// `options.poll_interval` 주기로 파일 시스템을 확인한다. 변경 이벤트는
+/// `options.debounce` 동안 모은 후 파일 단위로 합쳐서 한 번에 전달한다.
+/// 감시가 중단되거나 `options.cancel`이 취소되기 전까지 반환하지 않는다.
+pub fn watch(
+    options: &WatchOptions,
+    filter: &SearchFilter,
+    mut on_changes: impl FnMut(Vec<FileChange>),
+) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let _watcher = create_watcher(options, tx)?;
+
+    // 경로별로 하위 파일 전체를 확인해야 하는지 여부
+    let mut pending = HashMap::new();
+    loop {
+        let event = if pending.is_empty() {
+            if options.cancel.is_cancelled() {
+                break;
+            }
+            match rx.recv_timeout(CANCEL_CHECK_INTERVAL) {
+                Err(RecvTimeoutError::Timeout) => continue,
+                event => event,
+            }
+        } else {
+            rx.recv_timeout(options.debounce)
+        };
+        match event {
+            Ok(Ok(event)) => {
+                // 파일을 읽기만 한 경우는 무시
+                if !matches!(event.kind, EventKind::Access(_)) {
+                    let moved_in = is_moved_in(&event.kind);
+                    for path in event.paths {
+                        *pending.entry(path).or_default() |= moved_in;
+                    }
+                }
+            }
+            Ok(Err(e)) => eprintln!("Watch error: {e}"),
+            Err(RecvTimeoutError::Timeout) => {
+                let changes = collect_changes(pending.drain(), filter);
+                if !changes.is_empty() {
+                    on_changes(changes);
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// OS의 파일 변경 알림을 사용하는 감시자를 생성하고, 실패하면 주기적으로 확인하는 감시자를 생성하는 함수
+fn create_watcher(
+    options: &WatchOptions,
+    tx: mpsc::Sender<notify::Result<notify::Event>>,
+) -> Result<Box<dyn Watcher>, String> {
+    let recommended = notify::recommended_watcher(tx.clone()).and_then(|mut watcher| {
+        for root in &options.roots {
+            watcher.watch(root, RecursiveMode::Recursive)?;
+        }
+        Ok(watcher)
+    });
+    match recommended {
+        Ok(watcher) => return Ok(Box::new(watcher)),
+        // inotify의 감시 개수 제한을 넘은 경우 등
+        Err(e) => eprintln!("Failed to watch with OS notifications, falling back to polling: {e}"),
+    }
+
+    let mut watcher = PollWatcher::new(
+        tx,
+        Config::default().with_poll_interval(options.poll_interval),
+    )
+    .map_err(|e| e.to_string())?;
+    for root in &options.roots {
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .map_err(|e| format!("{}: {e}", root.display()))?;
+    }
+
+    Ok(Box::new(watcher))
+}
+
+/// 생성되었거나 이름이 변경되어 새로 나타난 경로에 대한 이벤트인지 확인하는 함수
+///
+/// 디렉토리가 통째로 생성되거나 이동된 경우에는 하위 파일에 대한 이벤트가 발생하지 않을 수 있다.
+fn is_moved_in(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))
+    )
+}
+
+/// 변경 이벤트가 발생한 경로들의 현재 상태를 확인하여 변경 사항을 생성하는 함수
+///
+/// 디렉토리는 새로 나타난 경우에만 하위 파일을 저장하고, 하위 파일이 바뀌어 발생한 디렉토리의
+/// 수정 이벤트는 무시한다. 하위 파일의 변경은 파일 자신의 이벤트로 반영된다.
+fn collect_changes(
+    paths: impl Iterator<Item = (PathBuf, bool)>,
+    filter: &SearchFilter,
+) -> Vec<FileChange> {
+    let mut changes = Vec::new();
+    for (path, moved_in) in paths {
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            // 삭제되었거나 다른 곳으로 이동된 경우 (확장자가 없으면 디렉토리일 수 있음)
+            if filter.matches_extension(&path) || path.extension().is_none() {
+                changes.push(FileChange::Removed(path));
+            }
+            continue;
+        };
+
+        if metadata.is_dir() {
+            if !moved_in {
+                continue;
+            }
+            changes.extend(
+                collect_files(path, filter)
+                    .into_iter()
+                    .map(FileChange::Upserted),
+            );
+        } else if metadata.is_file() {
+            let hit = FileHit::from_metadata(path, &metadata, HitSource::Watch);
+            if filter.matches(&hit) {
+                changes.push(FileChange::Upserted(hit));
+            }
+        }
+    }
+
+    changes
+}
+
+/// 디렉토리의 모든 하위 파일 중 `filter`에 해당하는 파일을 찾는 함수
+fn collect_files(directory: PathBuf, filter: &SearchFilter) -> Vec<FileHit> {
+    let mut files = Vec::new();
+    let mut directories = vec![directory];
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                directories.push(entry.path());
+            } else if metadata.is_file() {
+                let hit = FileHit::from_metadata(entry.path(), &metadata, HitSource::Watch);
+                if filter.matches(&hit) {
+                    files.push(hit);
+                }
+            }
+        }
+    }
+
+    files
+}